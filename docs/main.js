@@ -1,18 +1,25 @@
-import init, { solve_words, zodiac_words } from "./pkg/anagram_design.js";
+import init, { Solver, evaluate_tree, word_sets } from "./pkg/anagram_design.js";
 
 const wordsField = document.querySelector("#words");
+const wordSetField = document.querySelector("#word-set");
 const allowRepeatField = document.querySelector("#allow-repeat");
 const prioritizeSoftField = document.querySelector("#prioritize-soft");
 const redeemingYesField = document.querySelector("#redeeming-yes");
+const maxDepthField = document.querySelector("#max-depth");
+const maxConsecutiveNosField = document.querySelector("#max-consecutive-nos");
 const statusEl = document.querySelector("#status");
 const summaryEl = document.querySelector("#summary");
 const treesEl = document.querySelector("#trees");
 const formEl = document.querySelector("#solver-form");
+const cancelButton = document.querySelector("#cancel");
+const treeNotationField = document.querySelector("#tree-notation");
+const treeCheckEl = document.querySelector("#tree-check");
+const treeTextEl = document.querySelector("#tree-text");
 
 const wasmReady = init();
 
 // LocalStorage key for storing user choices
-const STORAGE_KEY = "anagram_tree_choices";
+const STORAGE_KEY = "anagram_tree_picks";
 
 // Auto-resize textarea based on content
 function autoResizeTextarea() {
@@ -49,27 +56,46 @@ function setWords(words) {
   autoResizeTextarea();
 }
 
-// Get user's selected option index for a given tree path (default to 0)
-function getSelectedOption(path) {
+// User's picks as [path, option index] pairs, most recent first
+function loadPicks() {
   try {
-    const choices = JSON.parse(localStorage.getItem(STORAGE_KEY) || "{}");
-    return choices[path] ?? 0;
+    const picks = JSON.parse(localStorage.getItem(STORAGE_KEY) || "[]");
+    return Array.isArray(picks) ? picks : [];
   } catch {
-    return 0;
+    return [];
   }
 }
 
-// Save user's selection for a tree path
-function saveSelection(path, optionIndex) {
+function storePicks(picks) {
   try {
-    const choices = JSON.parse(localStorage.getItem(STORAGE_KEY) || "{}");
-    choices[path] = optionIndex;
-    localStorage.setItem(STORAGE_KEY, JSON.stringify(choices));
+    localStorage.setItem(STORAGE_KEY, JSON.stringify(picks));
   } catch (err) {
     console.error("Failed to save selection:", err);
   }
 }
 
+// Save user's selection for a tree path; it takes precedence over earlier picks
+function saveSelection(path, optionIndex) {
+  storePicks([[path, optionIndex], ...loadPicks().filter(([other]) => other !== path)]);
+}
+
+// The tree the current picks resolve to (see MergedNode::resolve), once a search has finished
+let resolution = null;
+
+function resolvedChoice(path) {
+  return resolution?.choices.find(choice => choice.path === path);
+}
+
+// Option shown at a tree path: the resolved tree's, defaulting to the first
+function getSelectedOption(path) {
+  return resolvedChoice(path)?.selected ?? 0;
+}
+
+// Whether an option still leads to an optimal tree given the user's other picks
+function isValidOption(path, optionIndex) {
+  return resolvedChoice(path)?.valid.includes(optionIndex) ?? true;
+}
+
 // Capitalize first letter
 function capitalizeFirst(word) {
   if (!word) return word;
@@ -352,6 +378,22 @@ function renderSpine(mergedNode, path, prefix, isFinal, out) {
   }
 }
 
+// The solver sends the merged tree as a DAG, each distinct node once with the root first and
+// children referenced by index; link the references so shared subtrees become shared objects
+function linkMergedTree({ nodes }) {
+  for (const node of nodes) {
+    for (const option of node.options) {
+      if (option.yesBranch !== undefined) {
+        option.yesBranch = nodes[option.yesBranch];
+      }
+      if (option.noBranch !== undefined) {
+        option.noBranch = nodes[option.noBranch];
+      }
+    }
+  }
+  return nodes[0];
+}
+
 // Render the merged tree to HTML
 function renderMergedTree(mergedTree) {
   const out = { lines: [], choices: [] };
@@ -388,13 +430,21 @@ function createDropdown(options, path, currentIdx) {
     const item = document.createElement("div");
     item.className = "dropdown-item";
     const isSelected = idx === currentIdx;
+    const isValid = isValidOption(path, idx);
     item.style.cssText = `
       padding: 0.25rem 0.75rem;
       cursor: pointer;
       white-space: nowrap;
+      ${isValid ? "" : "opacity: 0.5;"}
       ${isSelected ? 'background: rgba(96, 165, 250, 0.2); border-left: 3px solid var(--choice-color, #60a5fa);' : 'border-left: 3px solid transparent;'}
     `;
-    item.textContent = formatNodeInfo(opt.info);
+    // Per-option statistics computed by MergedNode::merge
+    const trees = `${opt.treeCount} tree${opt.treeCount === 1 ? "" : "s"}`;
+    item.textContent = `${formatNodeInfo(opt.info)} · ${trees}, up to ${opt.maxDepth} questions`;
+    item.title = `Words: ${opt.words.map(capitalizeFirst).join(", ")}\nLetters: ${opt.letters.toUpperCase() || "none"}`;
+    if (!isValid) {
+      item.title += "\nConflicts with your other picks; choosing it overrides them";
+    }
 
     item.addEventListener("mouseenter", () => {
       if (!isSelected) {
@@ -461,11 +511,48 @@ function attachChoiceHandlers(choices) {
   });
 }
 
+function describeCost(cost) {
+  return `
+    Maximum number of "No" answers: ${cost.max_nos} (average: ${cost.avg_nos.toFixed(2)}) ·
+    Maximum number of unjustified "No" answers: ${cost.max_hard_nos} (average: ${cost.avg_hard_nos.toFixed(2)}) ·
+    Maximum number of questions: ${cost.max_questions} (average: ${cost.avg_questions.toFixed(2)}) ·
+    Longest run of "No" answers: ${cost.max_consecutive_nos}
+  `;
+}
+
+// Check and render the tree in the editor; the cost and checks come from the Rust side
+function evaluateEditedTree() {
+  const notation = treeNotationField.value.trim();
+  if (!notation) {
+    treeCheckEl.textContent = "";
+    treeTextEl.textContent = "";
+    return;
+  }
+  try {
+    const evaluation = evaluate_tree(notation, parseWords(), parseInt(redeemingYesField.value, 10));
+    treeTextEl.textContent = evaluation.text;
+    treeCheckEl.dataset.tone = evaluation.issues.length ? "error" : "neutral";
+    treeCheckEl.textContent = evaluation.issues.length
+      ? evaluation.issues.join("\n")
+      : `Valid. ${describeCost(evaluation.cost).replace(/\s+/g, " ").trim()}`;
+  } catch (err) {
+    treeTextEl.textContent = "";
+    treeCheckEl.dataset.tone = "error";
+    treeCheckEl.textContent = err instanceof Error ? err.message : String(err);
+  }
+}
+
 function renderResult(result) {
   window.currentResult = result; // Store for re-rendering
 
   const { cost, merged_tree: mergedTree } = result;
 
+  // Resolve the picks to a concrete tree; picks that no longer apply are forgotten
+  resolution = resultSolver.resolve(loadPicks());
+  if (resolution.ignored.length) {
+    storePicks(loadPicks().filter(([path]) => !resolution.ignored.includes(path)));
+  }
+
   const { html, choices } = renderMergedTree(mergedTree);
 
   const hasChoices = choices.length > 0;
@@ -481,50 +568,138 @@ function renderResult(result) {
   `;
 
   // Cost summary goes below the tree
-  const summaryText = `
-    Maximum number of "No" answers: ${cost.max_nos} (average: ${cost.avg_nos.toFixed(2)}) ·
-    Maximum number of unjustified "No" answers: ${cost.max_hard_nos} (average: ${cost.avg_hard_nos.toFixed(2)})
-  `;
-  summaryEl.innerHTML = `<p>${summaryText}</p>`;
+  const trees = `${result.tree_count} optimal tree${result.tree_count === 1 ? "" : "s"}`;
+  const routines = `${result.routine_count} distinct routine${result.routine_count === 1 ? "" : "s"}`;
+  summaryEl.innerHTML = `<p>${describeCost(cost)}</p><p>${trees} · ${routines}</p>`;
 
   // Attach click handlers to choice nodes
   attachChoiceHandlers(choices);
+
+  treeNotationField.value = resolution.tree;
+  evaluateEditedTree();
+}
+
+// Subproblems the solver expands between two repaints; small enough to keep the page responsive
+const STEP_BUDGET = 20000;
+
+// The search in progress, if any
+let activeSolver = null;
+// The finished search shown on the page, kept to resolve the user's picks
+let resultSolver = null;
+
+function yieldToBrowser() {
+  return new Promise((resolve) => setTimeout(resolve, 0));
+}
+
+// Abandon the search in progress; its loop notices on its next step and frees it
+function cancelSolver(message) {
+  if (activeSolver) {
+    activeSolver.cancel();
+    activeSolver = null;
+    cancelButton.hidden = true;
+    if (message) {
+      setStatus(message);
+    }
+  }
 }
 
 async function runSolver(event) {
   event?.preventDefault();
   await wasmReady;
+  cancelSolver();
 
   const words = parseWords();
+  const optionalInt = (field) => (field.value === "" ? undefined : parseInt(field.value, 10));
+  let solver;
+  try {
+    solver = new Solver(words, {
+      allow_repeat: allowRepeatField.checked,
+      prioritize_soft_no: prioritizeSoftField.checked,
+      redeeming_yes: parseInt(redeemingYesField.value, 10),
+      max_depth: optionalInt(maxDepthField),
+      max_consecutive_nos: optionalInt(maxConsecutiveNosField),
+      naturalness: {}, // Default weights, so the most natural tree is shown first
+    });
+  } catch (err) {
+    console.error(err);
+    setStatus(err instanceof Error ? err.message : String(err), "error");
+    return;
+  }
 
+  activeSolver = solver;
+  cancelButton.hidden = false;
   try {
     setStatus("Generating…");
-    const redeemingYes = parseInt(redeemingYesField.value, 10);
-    const result = solve_words(
-      words,
-      allowRepeatField.checked,
-      prioritizeSoftField.checked,
-      redeemingYes
-    );
+    let progress = solver.step(STEP_BUDGET);
+    while (!progress.done) {
+      setStatus(`Generating… ${progress.expanded.toLocaleString()} subproblems explored`);
+      await yieldToBrowser();
+      if (activeSolver !== solver) {
+        return; // Cancelled, or replaced by a newer search
+      }
+      progress = solver.step(STEP_BUDGET);
+    }
+    const result = solver.result();
+    result.merged_tree = linkMergedTree(result.merged_tree);
+    resultSolver?.free();
+    resultSolver = solver;
     renderResult(result);
     setStatus("");
   } catch (err) {
     console.error(err);
     const message = err instanceof Error ? err.message : String(err);
     setStatus(message, "error");
+  } finally {
+    if (activeSolver === solver) {
+      activeSolver = null;
+      cancelButton.hidden = true;
+    }
+    if (resultSolver !== solver) {
+      solver.free();
+    }
+  }
+}
+
+// Built-in word sets by name, filled once the WASM module is ready
+const wordSetsByName = new Map();
+
+function selectWordSet(name) {
+  const set = wordSetsByName.get(name);
+  if (set) {
+    setWords(set.words.map(capitalizeFirst));
   }
 }
 
 async function initDefaults() {
   await wasmReady;
-  const defaults = zodiac_words();
-  if (Array.isArray(defaults)) {
-    setWords(defaults.map(capitalizeFirst));
+  for (const set of word_sets()) {
+    wordSetsByName.set(set.name, set);
+    const option = document.createElement("option");
+    option.value = set.name;
+    option.textContent = `${capitalizeFirst(set.name.replaceAll("-", " "))} (${set.words.length})`;
+    option.title = set.description;
+    wordSetField.append(option);
   }
+  wordSetField.value = "zodiac";
+  selectWordSet("zodiac");
   runSolver();
 }
 
 formEl.addEventListener("submit", runSolver);
+cancelButton.addEventListener("click", () => cancelSolver("Cancelled."));
+treeNotationField.addEventListener("input", evaluateEditedTree);
+
+wordSetField.addEventListener("change", () => {
+  if (wordSetField.value) {
+    selectWordSet(wordSetField.value);
+    runSolver();
+  }
+});
+
+// Editing the words by hand switches the dropdown back to a custom list
+wordsField.addEventListener("input", () => {
+  wordSetField.value = "";
+});
 
 initDefaults().catch((err) => {
   console.error(err);
@@ -1,6 +1,8 @@
 let wasm;
 
-let WASM_VECTOR_LEN = 0;
+const cachedTextDecoder = (typeof TextDecoder !== 'undefined' ? new TextDecoder('utf-8', { ignoreBOM: true, fatal: true }) : { decode: () => { throw Error('TextDecoder not available') } } );
+
+if (typeof TextDecoder !== 'undefined') { cachedTextDecoder.decode(); };
 
 let cachedUint8ArrayMemory0 = null;
 
@@ -11,6 +13,13 @@ function getUint8ArrayMemory0() {
     return cachedUint8ArrayMemory0;
 }
 
+function getStringFromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return cachedTextDecoder.decode(getUint8ArrayMemory0().subarray(ptr, ptr + len));
+}
+
+let WASM_VECTOR_LEN = 0;
+
 const cachedTextEncoder = (typeof TextEncoder !== 'undefined' ? new TextEncoder('utf-8') : { encode: () => { throw Error('TextEncoder not available') } } );
 
 const encodeString = (typeof cachedTextEncoder.encodeInto === 'function'
@@ -78,15 +87,6 @@ function getDataViewMemory0() {
     return cachedDataViewMemory0;
 }
 
-const cachedTextDecoder = (typeof TextDecoder !== 'undefined' ? new TextDecoder('utf-8', { ignoreBOM: true, fatal: true }) : { decode: () => { throw Error('TextDecoder not available') } } );
-
-if (typeof TextDecoder !== 'undefined') { cachedTextDecoder.decode(); };
-
-function getStringFromWasm0(ptr, len) {
-    ptr = ptr >>> 0;
-    return cachedTextDecoder.decode(getUint8ArrayMemory0().subarray(ptr, ptr + len));
-}
-
 function debugString(val) {
     // primitive types
     const type = typeof val;
@@ -151,36 +151,163 @@ function debugString(val) {
     // TODO we could test for more things here, like `Set`s and `Map`s.
     return className;
 }
+
+function takeFromExternrefTable0(idx) {
+    const value = wasm.__wbindgen_export_2.get(idx);
+    wasm.__externref_table_dealloc(idx);
+    return value;
+}
+/**
+ * `diff` of two trees in compact notation: changed questions, moved subtrees and words whose
+ * path changed, plus the `format_diff` text annotating the second tree. `repeat_cost`
+ * (optional) is what a Repeat's No edge counts as in the word statistics.
+ * @param {string} before
+ * @param {string} after
+ * @param {any} repeat_cost
+ * @returns {any}
+ */
+export function diff_trees(before, after, repeat_cost) {
+    const ptr0 = passStringToWasm0(before, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ptr1 = passStringToWasm0(after, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len1 = WASM_VECTOR_LEN;
+    const ret = wasm.diff_trees(ptr0, len0, ptr1, len1, repeat_cost);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * Words of the built-in set called `name` (see `wordsets::by_name`).
+ * @param {string} name
+ * @returns {any}
+ */
+export function word_set(name) {
+    const ptr0 = passStringToWasm0(name, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.word_set(ptr0, len0);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
 /**
- * Convenience helper exposed to JS: return the Zodiac word list.
+ * Cost (as in `solve_words`), validation issues, word paths and `format_tree` text of a tree
+ * edited by the user, in compact notation. `repeat_cost` (optional) is what a Repeat's No
+ * edge counts as, like `SolverConfig::repeat_cost`.
+ * @param {string} tree
+ * @param {any} words
+ * @param {number} redeeming_yes
+ * @param {any} repeat_cost
  * @returns {any}
  */
-export function zodiac_words() {
-    const ret = wasm.zodiac_words();
+export function evaluate_tree(tree, words, redeeming_yes, repeat_cost) {
+    const ptr0 = passStringToWasm0(tree, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.evaluate_tree(ptr0, len0, words, redeeming_yes, repeat_cost);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * Built-in word sets (`name`, `description`, `words`) for the web UI's dropdown.
+ * @returns {any}
+ */
+export function word_sets() {
+    const ret = wasm.word_sets();
     return ret;
 }
 
-function takeFromExternrefTable0(idx) {
-    const value = wasm.__wbindgen_export_2.get(idx);
-    wasm.__externref_table_dealloc(idx);
-    return value;
+/**
+ * Per-word path reports (questions, expected answers, No counts) of a tree in compact notation,
+ * counting a Repeat's No edge as the optional `repeat_cost` says.
+ * @param {string} tree
+ * @param {any} repeat_cost
+ * @returns {any}
+ */
+export function tree_paths(tree, repeat_cost) {
+    const ptr0 = passStringToWasm0(tree, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.tree_paths(ptr0, len0, repeat_cost);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
 }
+
+/**
+ * `validate_tree` problems of a tree in compact notation, as messages. With `words`, also
+ * checks that the tree reaches exactly those words.
+ * @param {string} tree
+ * @param {any} words
+ * @returns {any}
+ */
+export function tree_issues(tree, words) {
+    const ptr0 = passStringToWasm0(tree, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.tree_issues(ptr0, len0, words);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
 /**
  * WebAssembly entry point: solve for the provided words and return all optimal trees.
+ * `max_depth` caps the number of questions on any path; an infeasible cap is reported
+ * as an error naming the minimum feasible depth. `max_consecutive_nos` caps runs of No answers.
  * @param {any} words
  * @param {boolean} allow_repeat
  * @param {boolean} prioritize_soft_no
  * @param {number} redeeming_yes
+ * @param {number | undefined} [max_depth]
+ * @param {number | undefined} [max_consecutive_nos]
  * @returns {any}
  */
-export function solve_words(words, allow_repeat, prioritize_soft_no, redeeming_yes) {
-    const ret = wasm.solve_words(words, allow_repeat, prioritize_soft_no, redeeming_yes);
+export function solve_words(words, allow_repeat, prioritize_soft_no, redeeming_yes, max_depth, max_consecutive_nos) {
+    const ret = wasm.solve_words(words, allow_repeat, prioritize_soft_no, redeeming_yes, !isLikeNone(max_depth), isLikeNone(max_depth) ? 0 : max_depth, !isLikeNone(max_consecutive_nos), isLikeNone(max_consecutive_nos) ? 0 : max_consecutive_nos);
     if (ret[2]) {
         throw takeFromExternrefTable0(ret[1]);
     }
     return takeFromExternrefTable0(ret[0]);
 }
 
+/**
+ * Render a tree written in compact notation as `ascii`, `notation`, `dot`, `mermaid`,
+ * `script`, `svg` or `paths`, like the command-line `render`. `repeat_cost` (optional)
+ * is what a Repeat's No edge counts as in `paths`.
+ * @param {string} tree
+ * @param {string} format
+ * @param {any} repeat_cost
+ * @returns {string}
+ */
+export function render_tree(tree, format, repeat_cost) {
+    let deferred4_0;
+    let deferred4_1;
+    try {
+        const ptr0 = passStringToWasm0(tree, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(format, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.render_tree(ptr0, len0, ptr1, len1, repeat_cost);
+        var ptr3 = ret[0];
+        var len3 = ret[1];
+        if (ret[3]) {
+            ptr3 = 0; len3 = 0;
+            throw takeFromExternrefTable0(ret[2]);
+        }
+        deferred4_0 = ptr3;
+        deferred4_1 = len3;
+        return getStringFromWasm0(ptr3, len3);
+    } finally {
+        wasm.__wbindgen_free(deferred4_0, deferred4_1, 1);
+    }
+}
+
 function addToExternrefTable0(obj) {
     const idx = wasm.__externref_table_alloc();
     wasm.__wbindgen_export_2.set(idx, obj);
@@ -196,6 +323,108 @@ function handleError(f, args) {
     }
 }
 
+const SolverFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_solver_free(ptr >>> 0, 1));
+/**
+ * Stepwise solver that keeps the page responsive: call `step` from an idle loop or a Web
+ * Worker until it reports `done`, showing its progress in between, then read `result`.
+ * `cancel` (or `free`) abandons the search.
+ */
+export class Solver {
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        SolverFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_solver_free(ptr, 0);
+    }
+    /**
+     * `config` takes `SolverConfig` fields (`allow_repeat`, `max_repeats`, `repeat_cost`,
+     * `prioritize_soft_no`, `redeeming_yes`, `weights`, `max_depth`, `max_consecutive_nos`,
+     * `naturalness`); missing ones keep their defaults. `naturalness` is off by default, so
+     * trees come in search order; pass `NaturalnessWeights` fields (`{}` for the defaults) to
+     * get the most natural tree first, as the web page does.
+     * @param {any} words
+     * @param {any} config
+     */
+    constructor(words, config) {
+        const ret = wasm.solver_new(words, config);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        this.__wbg_ptr = ret[0] >>> 0;
+        SolverFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Expand up to `budget` subproblems, returning the progress
+     * (`expanded`, `memo_entries`, `done`, `cancelled`).
+     * @param {number} budget
+     * @returns {any}
+     */
+    step(budget) {
+        const ret = wasm.solver_step(this.__wbg_ptr, budget);
+        return ret;
+    }
+    /**
+     * The solution object of `result`, restricted to the optimal trees matching `query`
+     * (see `Query` for the syntax); an error if the query is invalid or nothing matches.
+     * @param {string} query
+     * @returns {any}
+     */
+    query(query) {
+        const ptr0 = passStringToWasm0(query, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.solver_query(this.__wbg_ptr, ptr0, len0);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+    cancel() {
+        wasm.solver_cancel(this.__wbg_ptr);
+    }
+    /**
+     * The same solution object as `solve_words`, once `step` reports `done`.
+     * @returns {any}
+     */
+    result() {
+        const ret = wasm.solver_result(this.__wbg_ptr);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+    /**
+     * Resolve the user's picks in the merged tree to one concrete tree. `picks` is an array of
+     * `[path, option]` pairs, most important first; see `MergedNode::resolve`. Returns the tree
+     * (`tree` in notation and `text`), every choice node on it with its `selected` and `valid`
+     * options, and the paths of `ignored` picks.
+     * @param {any} picks
+     * @returns {any}
+     */
+    resolve(picks) {
+        const ret = wasm.solver_resolve(this.__wbg_ptr, picks);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+    /**
+     * @returns {any}
+     */
+    progress() {
+        const ret = wasm.solver_progress(this.__wbg_ptr);
+        return ret;
+    }
+}
+
 async function __wbg_load(module, imports) {
     if (typeof Response === 'function' && module instanceof Response) {
         if (typeof WebAssembly.instantiateStreaming === 'function') {
@@ -230,6 +459,10 @@ async function __wbg_load(module, imports) {
 function __wbg_get_imports() {
     const imports = {};
     imports.wbg = {};
+    imports.wbg.__wbindgen_string_new = function(arg0, arg1) {
+        const ret = getStringFromWasm0(arg0, arg1);
+        return ret;
+    };
     imports.wbg.__wbindgen_string_get = function(arg0, arg1) {
         const obj = arg1;
         const ret = typeof(obj) === 'string' ? obj : undefined;
@@ -238,12 +471,20 @@ function __wbg_get_imports() {
         getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
         getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
     };
-    imports.wbg.__wbindgen_string_new = function(arg0, arg1) {
-        const ret = getStringFromWasm0(arg0, arg1);
+    imports.wbg.__wbindgen_is_bigint = function(arg0) {
+        const ret = typeof(arg0) === 'bigint';
         return ret;
     };
-    imports.wbg.__wbindgen_jsval_loose_eq = function(arg0, arg1) {
-        const ret = arg0 == arg1;
+    imports.wbg.__wbindgen_bigint_from_u64 = function(arg0) {
+        const ret = BigInt.asUintN(64, arg0);
+        return ret;
+    };
+    imports.wbg.__wbindgen_jsval_eq = function(arg0, arg1) {
+        const ret = arg0 === arg1;
+        return ret;
+    };
+    imports.wbg.__wbindgen_error_new = function(arg0, arg1) {
+        const ret = new Error(getStringFromWasm0(arg0, arg1));
         return ret;
     };
     imports.wbg.__wbindgen_boolean_get = function(arg0) {
@@ -251,19 +492,39 @@ function __wbg_get_imports() {
         const ret = typeof(v) === 'boolean' ? (v ? 1 : 0) : 2;
         return ret;
     };
+    imports.wbg.__wbindgen_is_string = function(arg0) {
+        const ret = typeof(arg0) === 'string';
+        return ret;
+    };
+    imports.wbg.__wbindgen_is_object = function(arg0) {
+        const val = arg0;
+        const ret = typeof(val) === 'object' && val !== null;
+        return ret;
+    };
+    imports.wbg.__wbindgen_is_undefined = function(arg0) {
+        const ret = arg0 === undefined;
+        return ret;
+    };
+    imports.wbg.__wbindgen_in = function(arg0, arg1) {
+        const ret = arg0 in arg1;
+        return ret;
+    };
     imports.wbg.__wbindgen_number_get = function(arg0, arg1) {
         const obj = arg1;
         const ret = typeof(obj) === 'number' ? obj : undefined;
         getDataViewMemory0().setFloat64(arg0 + 8 * 1, isLikeNone(ret) ? 0 : ret, true);
         getDataViewMemory0().setInt32(arg0 + 4 * 0, !isLikeNone(ret), true);
     };
-    imports.wbg.__wbindgen_is_object = function(arg0) {
-        const val = arg0;
-        const ret = typeof(val) === 'object' && val !== null;
+    imports.wbg.__wbindgen_is_null = function(arg0) {
+        const ret = arg0 === null;
         return ret;
     };
-    imports.wbg.__wbindgen_error_new = function(arg0, arg1) {
-        const ret = new Error(getStringFromWasm0(arg0, arg1));
+    imports.wbg.__wbindgen_jsval_loose_eq = function(arg0, arg1) {
+        const ret = arg0 == arg1;
+        return ret;
+    };
+    imports.wbg.__wbg_getwithrefkey_5e6d9547403deab8 = function(arg0, arg1) {
+        const ret = arg0[arg1];
         return ret;
     };
     imports.wbg.__wbg_set_841ac57cff3d672b = function(arg0, arg1, arg2) {
@@ -346,6 +607,14 @@ function __wbg_get_imports() {
         const ret = Array.isArray(arg0);
         return ret;
     };
+    imports.wbg.__wbg_isSafeInteger_b9dff570f01a9100 = function(arg0) {
+        const ret = Number.isSafeInteger(arg0);
+        return ret;
+    };
+    imports.wbg.__wbg_entries_c02034de337d3ee2 = function(arg0) {
+        const ret = Object.entries(arg0);
+        return ret;
+    };
     imports.wbg.__wbg_iterator_695d699a44d6234c = function() {
         const ret = Symbol.iterator;
         return ret;
@@ -374,6 +643,12 @@ function __wbg_get_imports() {
         const ret = Reflect.get(arg0, arg1);
         return ret;
     }, arguments) };
+    imports.wbg.__wbindgen_bigint_get_as_i64 = function(arg0, arg1) {
+        const v = arg1;
+        const ret = typeof(v) === 'bigint' ? v : undefined;
+        getDataViewMemory0().setBigInt64(arg0 + 8 * 1, isLikeNone(ret) ? BigInt(0) : ret, true);
+        getDataViewMemory0().setInt32(arg0 + 4 * 0, !isLikeNone(ret), true);
+    };
     imports.wbg.__wbindgen_memory = function() {
         const ret = wasm.memory;
         return ret;
@@ -1,9 +1,38 @@
+use std::fmt;
+
 use hashbrown::HashMap;
 
 use crate::node::Solution;
+use crate::config::SolverConfig;
 use crate::context::{Context, Mask};
 use crate::constraints::Constraints;
-use crate::dijkstra_solver::solve;
+use crate::cost::min_questions;
+use crate::dijkstra_solver::{solve, Budget, Key};
+
+/// Returned when no tree fits within the configured `max_depth`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthInfeasible {
+    /// The cap that was requested.
+    pub max_depth: u32,
+    /// The smallest cap that admits a tree, `None` if no cap does.
+    pub min_feasible_depth: Option<u32>,
+    /// The configured `max_consecutive_nos`, which still applies without a cap.
+    pub max_consecutive_nos: Option<u32>,
+}
+
+impl fmt::Display for DepthInfeasible {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.min_feasible_depth {
+            Some(depth) => write!(f, "no tree fits within {} questions (minimum feasible depth: {depth})", self.max_depth),
+            None => match self.max_consecutive_nos {
+                Some(run) => write!(f, "no tree separates these words with at most {run} consecutive No answers"),
+                None => write!(f, "no tree separates these words"),
+            },
+        }
+    }
+}
+
+impl std::error::Error for DepthInfeasible {}
 
 /// Compute all optimal trees for the given word list.
 pub fn minimal_trees(
@@ -12,9 +41,112 @@ pub fn minimal_trees(
     prioritize_soft_no: bool,
     redeeming_yes: u32,
 ) -> Solution {
-    assert!(words.len() <= 32, "bitmask solver supports up to 32 words");
-    let ctx = Context::new(words);
-    let mask = if words.len() == 32 { Mask::MAX } else { ((1 as Mask) << words.len()) - 1 };
-    let mut memo = HashMap::new();
-    solve(mask, &ctx, allow_repeat, prioritize_soft_no, redeeming_yes, Constraints::empty(), &mut memo)
+    minimal_trees_with_config(words, &SolverConfig::new(allow_repeat, prioritize_soft_no, redeeming_yes))
+}
+
+/// Compute all optimal trees for the given word list using the full solver configuration.
+/// Returns an unsolvable solution (no trees) when `max_depth` cannot be met.
+pub fn minimal_trees_with_config(words: &[String], config: &SolverConfig) -> Solution {
+    solve_all(&Context::new(words), config, config.max_depth, &mut HashMap::new())
+}
+
+/// Like `minimal_trees_with_config`, with `aliases[idx]` listing alternate spellings of `words[idx]`
+/// (e.g. misspellings a spectator might use). Every question on a word's path gives the same
+/// answer for all of its spellings, so each spelling reaches the word's leaf.
+pub fn minimal_trees_with_aliases(words: &[String], aliases: &[Vec<String>], config: &SolverConfig) -> Solution {
+    solve_all(&Context::with_aliases(words, aliases), config, config.max_depth, &mut HashMap::new())
+}
+
+/// Like `minimal_trees_with_config`, but reports the minimum feasible depth when the
+/// configured `max_depth` admits no tree.
+pub fn try_minimal_trees(words: &[String], config: &SolverConfig) -> Result<Solution, DepthInfeasible> {
+    try_minimal_trees_with_memo(words, config, &mut HashMap::new())
+}
+
+/// `try_minimal_trees` with alternate spellings, as in `minimal_trees_with_aliases`.
+pub fn try_minimal_trees_with_aliases(
+    words: &[String],
+    aliases: &[Vec<String>],
+    config: &SolverConfig,
+) -> Result<Solution, DepthInfeasible> {
+    try_solve(&Context::with_aliases(words, aliases), config, &mut HashMap::new())
+}
+
+/// `try_minimal_trees` with a caller-owned memo. The memo may be reused for the same words
+/// under configurations that differ only in `max_depth`.
+pub(crate) fn try_minimal_trees_with_memo(
+    words: &[String],
+    config: &SolverConfig,
+    memo: &mut HashMap<Key, Solution>,
+) -> Result<Solution, DepthInfeasible> {
+    try_solve(&Context::new(words), config, memo)
+}
+
+/// `try_minimal_trees` over all the words of `ctx`. The result is meaningless when the
+/// context's step limit interrupts the search (see `Context::interrupted`).
+pub(crate) fn try_solve(
+    ctx: &Context<'_>,
+    config: &SolverConfig,
+    memo: &mut HashMap<Key, Solution>,
+) -> Result<Solution, DepthInfeasible> {
+    let sol = solve_all(ctx, config, config.max_depth, memo);
+    match config.max_depth {
+        Some(max_depth) if sol.is_unsolvable() => Err(DepthInfeasible {
+            max_depth,
+            min_feasible_depth: search_min_depth(ctx, config, memo),
+            max_consecutive_nos: config.max_consecutive_nos,
+        }),
+        _ => Ok(sol),
+    }
+}
+
+/// Smallest `max_depth` for which `config` admits a tree, `None` if the words cannot be
+/// separated at all, or not within the configured `max_consecutive_nos`. The configured
+/// `max_depth` is ignored.
+pub fn min_feasible_depth(words: &[String], config: &SolverConfig) -> Option<u32> {
+    search_min_depth(&Context::new(words), config, &mut HashMap::new())
+}
+
+fn search_min_depth(ctx: &Context<'_>, config: &SolverConfig, memo: &mut HashMap<Key, Solution>) -> Option<u32> {
+    // Only feasibility matters here, so skip ranking the trees
+    let config = &SolverConfig { naturalness: None, ..config.clone() };
+    // The uncapped optimum is feasible, so its depth bounds the search from above
+    let uncapped = solve_all(ctx, config, None, memo);
+    if uncapped.is_unsolvable() {
+        return None;
+    }
+    // Capped entries are keyed by their remaining budget, so the memo is shared across caps
+    let lower = min_questions(ctx.words.len() as u32).0;
+    (lower..uncapped.cost.max_questions)
+        .find(|&depth| !solve_all(ctx, config, Some(depth), memo).is_unsolvable())
+        .or(Some(uncapped.cost.max_questions))
+}
+
+fn solve_all(
+    ctx: &Context<'_>,
+    config: &SolverConfig,
+    max_depth: Option<u32>,
+    memo: &mut HashMap<Key, Solution>,
+) -> Solution {
+    let count = ctx.words.len();
+    assert!(count <= 32, "bitmask solver supports up to 32 words");
+    let mask = if count == 32 { Mask::MAX } else { ((1 as Mask) << count) - 1 };
+    solve_subset(ctx, mask, config, max_depth, memo)
+}
+
+/// Solve the words of `ctx` selected by `mask`. Masks index into the context's words,
+/// so one memo serves every subset of the same list under the same configuration.
+pub(crate) fn solve_subset(
+    ctx: &Context<'_>,
+    mask: Mask,
+    config: &SolverConfig,
+    max_depth: Option<u32>,
+    memo: &mut HashMap<Key, Solution>,
+) -> Solution {
+    let budget = Budget::new(max_depth, config.max_consecutive_nos);
+    let mut sol = solve(mask, ctx, config.repeat_budget(), config, Constraints::empty(), budget, memo);
+    if let Some(weights) = &config.naturalness {
+        sol.rank_by_naturalness(weights);
+    }
+    sol
 }
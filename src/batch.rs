@@ -0,0 +1,153 @@
+use std::time::Instant;
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::api::try_minimal_trees_with_memo;
+use crate::config::SolverConfig;
+use crate::cost::Cost;
+use crate::dijkstra_solver::Key;
+use crate::node::Solution;
+use crate::wordsets;
+
+/// One line of a JSON Lines batch, e.g. `{"id": 7, "words": ["aries", "leo"], "config": {"max_depth": 3}}`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BatchRequest {
+    /// Echoed back in the result, to match results with requests
+    pub id: Option<Value>,
+    pub words: Vec<String>,
+    /// Name of a built-in word set (see `wordsets::by_name`), used when `words` is empty
+    pub set: Option<String>,
+    /// `SolverConfig` fields overriding the batch's base configuration
+    pub config: Map<String, Value>,
+}
+
+/// Outcome of one batch line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchResult {
+    /// 1-based line number in the input
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub words: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<Cost>,
+    pub tree_count: usize,
+    /// First optimal tree, in compact notation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tree: Option<String>,
+    /// Time spent solving this line, in milliseconds
+    pub millis: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchResult {
+    fn failed(line: usize, id: Option<Value>, words: Vec<String>, error: String) -> Self {
+        BatchResult { line, id, words, cost: None, tree_count: 0, tree: None, millis: 0.0, error: Some(error) }
+    }
+}
+
+/// Solves a stream of batch requests, sharing the solver's memo between requests with
+/// the same words and the same configuration apart from `max_depth`.
+/// Memos are kept for the whole batch, so memory grows with the number of distinct lists.
+pub struct BatchSolver {
+    base: SolverConfig,
+    memos: Vec<(Vec<String>, SolverConfig, HashMap<Key, Solution>)>,
+}
+
+impl BatchSolver {
+    /// `base` applies to every request, with each request's `config` overriding it field by field.
+    pub const fn new(base: SolverConfig) -> Self {
+        BatchSolver { base, memos: Vec::new() }
+    }
+
+    fn config_for(&self, overrides: &Map<String, Value>) -> Result<SolverConfig, String> {
+        let mut config = serde_json::to_value(&self.base).map_err(|e| e.to_string())?;
+        let fields = config.as_object_mut().expect("SolverConfig serializes to an object");
+        for (key, value) in overrides {
+            if !fields.contains_key(key) {
+                return Err(format!("unknown config field '{key}'"));
+            }
+            fields.insert(key.clone(), value.clone());
+        }
+        serde_json::from_value(config).map_err(|e| format!("invalid config: {e}"))
+    }
+
+    fn memo_for(&mut self, words: &[String], config: &SolverConfig) -> &mut HashMap<Key, Solution> {
+        let shared = SolverConfig { max_depth: None, ..config.clone() };
+        let idx = match self.memos.iter().position(|(w, c, _)| w == words && *c == shared) {
+            Some(idx) => idx,
+            None => {
+                self.memos.push((words.to_vec(), shared, HashMap::new()));
+                self.memos.len() - 1
+            }
+        };
+        &mut self.memos[idx].2
+    }
+
+    /// Solve one request; failures are reported in the result's `error`.
+    pub fn solve(&mut self, line: usize, request: &BatchRequest) -> BatchResult {
+        let id = request.id.clone();
+        let words = match (&request.set, request.words.is_empty()) {
+            (Some(name), true) => match wordsets::by_name(name) {
+                Some(set) => set.to_words(),
+                None => return BatchResult::failed(line, id, Vec::new(), format!("unknown word set '{name}'")),
+            },
+            _ => request.words.clone(),
+        };
+        if words.is_empty() {
+            return BatchResult::failed(line, id, words, "no words given".to_string());
+        }
+        if words.len() > 32 {
+            return BatchResult::failed(line, id, words, "the solver supports up to 32 words".to_string());
+        }
+        let config = match self.config_for(&request.config) {
+            Ok(config) => config,
+            Err(error) => return BatchResult::failed(line, id, words, error),
+        };
+
+        let start = Instant::now();
+        let result = try_minimal_trees_with_memo(&words, &config, self.memo_for(&words, &config));
+        // Microsecond precision is plenty, and keeps the JSON readable
+        let millis = (start.elapsed().as_secs_f64() * 1e6).round() / 1e3;
+        match result {
+            Ok(sol) if !sol.is_unsolvable() => BatchResult {
+                line,
+                id,
+                words,
+                cost: Some(sol.cost),
+                tree_count: sol.trees.len(),
+                tree: Some(sol.trees[0].to_string()),
+                millis,
+                error: None,
+            },
+            Ok(_) => {
+                let error = "no tree satisfies these constraints".to_string();
+                BatchResult { millis, ..BatchResult::failed(line, id, words, error) }
+            }
+            Err(e) => BatchResult { millis, ..BatchResult::failed(line, id, words, e.to_string()) },
+        }
+    }
+
+    /// Parse and solve one JSON line; parse errors are reported in the result's `error`.
+    pub fn solve_json(&mut self, line: usize, text: &str) -> BatchResult {
+        match serde_json::from_str::<BatchRequest>(text) {
+            Ok(request) => self.solve(line, &request),
+            Err(e) => BatchResult::failed(line, None, Vec::new(), format!("invalid request: {e}")),
+        }
+    }
+}
+
+/// Solve every line of a JSON Lines batch, skipping blank lines and lines starting with `#`.
+pub fn solve_jsonl(input: &str, base: &SolverConfig) -> Vec<BatchResult> {
+    let mut solver = BatchSolver::new(base.clone());
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty() && !text.trim_start().starts_with('#'))
+        .map(|(idx, text)| solver.solve_json(idx + 1, text))
+        .collect()
+}
@@ -0,0 +1,124 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::Mask;
+use crate::cost::{admissible_estimate_cost, compare_costs, compare_weighted_costs, Cost, CostWeights};
+use crate::naturalness::NaturalnessWeights;
+
+/// What the No edge of a Repeat counts as in `Cost`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepeatCost {
+    /// Nobody answers a Repeat, so its No edge costs nothing and the run of Nos carries over.
+    #[default]
+    Free,
+    /// A soft No edge, counted and redeemed like one.
+    SoftNo,
+    /// A hard No edge, counted and redeemed like one.
+    HardNo,
+}
+
+impl RepeatCost {
+    /// Whether the No edge of a Repeat is a hard one, `None` when it is free.
+    pub const fn no_edge(self) -> Option<bool> {
+        match self {
+            RepeatCost::Free => None,
+            RepeatCost::SoftNo => Some(false),
+            RepeatCost::HardNo => Some(true),
+        }
+    }
+}
+
+/// Options controlling the search and how costs are ranked.
+/// Deserializing fills missing fields from `Default`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolverConfig {
+    /// Allow the "repeat in your mind" ploy (at most `max_repeats` times per path).
+    pub allow_repeat: bool,
+    /// Maximum number of Repeats on any path when `allow_repeat` is set.
+    pub max_repeats: u32,
+    /// What the No edge of each Repeat costs.
+    pub repeat_cost: RepeatCost,
+    /// Minimize hard No edges before all No edges (also used to break weighted ties).
+    pub prioritize_soft_no: bool,
+    /// Redemption applied to each No edge; `YesSplit`s redeem 1 each.
+    pub redeeming_yes: u32,
+    /// When set, costs are ranked by `weighted_cost` instead of lexicographically.
+    pub weights: Option<CostWeights>,
+    /// Maximum number of questions (including `YesSplit`s and Repeats) on any path.
+    pub max_depth: Option<u32>,
+    /// Maximum number of consecutive No answers on any path (Yes edges and `YesSplit`s end a run).
+    pub max_consecutive_nos: Option<u32>,
+    /// Order equally optimal trees by `naturalness`, most natural first; `None` (the default)
+    /// keeps the order the search found them in. Ranking scores every optimal tree, so it is
+    /// opt-in; the CLI and the web page turn it on.
+    pub naturalness: Option<NaturalnessWeights>,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig::new(false, false, 2)
+    }
+}
+
+impl SolverConfig {
+    /// Lexicographic configuration matching the `minimal_trees` arguments.
+    pub const fn new(allow_repeat: bool, prioritize_soft_no: bool, redeeming_yes: u32) -> Self {
+        SolverConfig {
+            allow_repeat,
+            max_repeats: 1,
+            repeat_cost: RepeatCost::Free,
+            prioritize_soft_no,
+            redeeming_yes,
+            weights: None,
+            max_depth: None,
+            max_consecutive_nos: None,
+            naturalness: None,
+        }
+    }
+
+    /// Repeats available at the root of a solve.
+    pub(crate) const fn repeat_budget(&self) -> u32 {
+        if self.allow_repeat { self.max_repeats } else { 0 }
+    }
+
+    /// Of `repeats_left` Repeats, those that can save a No edge: all of them when they are
+    /// free, none otherwise. This is what the cost estimates may count on.
+    pub(crate) const fn free_repeats(&self, repeats_left: u32) -> u32 {
+        match self.repeat_cost {
+            RepeatCost::Free => repeats_left,
+            RepeatCost::SoftNo | RepeatCost::HardNo => 0,
+        }
+    }
+
+    /// Order two costs according to the configured mode.
+    pub fn compare(&self, a: &Cost, b: &Cost) -> Ordering {
+        match &self.weights {
+            Some(weights) => compare_weighted_costs(a, b, weights, self.prioritize_soft_no),
+            None => compare_costs(a, b, self.prioritize_soft_no),
+        }
+    }
+
+    /// Combine a solved No branch (including its edge and sum contributions) with an
+    /// admissible estimate of the Yes branch, giving a lower bound on the whole split.
+    pub(crate) fn with_yes_estimate(&self, no_cost: Cost, yes_mask: Mask, free_repeats: u32) -> Cost {
+        let yes = admissible_estimate_cost(yes_mask, free_repeats, self.redeeming_yes);
+        Cost {
+            hard_nos: yes.hard_nos.max(no_cost.hard_nos),
+            redeemed_hard_nos: no_cost.redeemed_hard_nos,
+            nos: yes.nos.max(no_cost.nos),
+            redeemed_nos: (yes.redeemed_nos - 1).max(no_cost.redeemed_nos),
+            sum_hard_nos: yes.sum_hard_nos + no_cost.sum_hard_nos,
+            redeemed_sum_hard_nos: yes.redeemed_sum_hard_nos + no_cost.redeemed_sum_hard_nos,
+            sum_nos: yes.sum_nos + no_cost.sum_nos,
+            redeemed_sum_nos: yes.redeemed_sum_nos + no_cost.redeemed_sum_nos,
+            max_consecutive_nos: yes.max_consecutive_nos.max(no_cost.max_consecutive_nos),
+            leading_nos: no_cost.leading_nos,
+            max_questions: 1 + yes.max_questions.max(no_cost.max_questions),
+            sum_questions: yes.sum_questions + no_cost.sum_questions + yes.word_count + no_cost.word_count,
+            word_count: yes.word_count + no_cost.word_count,
+        }
+    }
+}
@@ -148,94 +148,104 @@ pub struct SoftNoPair {
     pub test_letter: char,
     /// Require all No items contain this letter
     pub requirement_letter: char,
+    /// Why the letters are confusable, phrased to justify a miss on stage
+    pub reason: &'static str,
 }
 
 /// Define the available soft no pairs
 /// Children of a soft no cannot use any soft no containing either letter
 pub const SOFT_NO_PAIRS: &[SoftNoPair] = &[
     // E/I pair - vowel similarity
-    SoftNoPair { test_letter: 'e', requirement_letter: 'i' },
-    SoftNoPair { test_letter: 'i', requirement_letter: 'e' },
+    SoftNoPair { test_letter: 'e', requirement_letter: 'i', reason: "they are such similar vowels" },
+    SoftNoPair { test_letter: 'i', requirement_letter: 'e', reason: "they are such similar vowels" },
     // C/K pair - identical hard sound
-    SoftNoPair { test_letter: 'c', requirement_letter: 'k' },
-    SoftNoPair { test_letter: 'k', requirement_letter: 'c' },
+    SoftNoPair { test_letter: 'c', requirement_letter: 'k', reason: "they make the same hard sound" },
+    SoftNoPair { test_letter: 'k', requirement_letter: 'c', reason: "they make the same hard sound" },
     // S/Z pair - similar sibilants
-    SoftNoPair { test_letter: 's', requirement_letter: 'z' },
-    SoftNoPair { test_letter: 'z', requirement_letter: 's' },
+    SoftNoPair { test_letter: 's', requirement_letter: 'z', reason: "they hiss the same way" },
+    SoftNoPair { test_letter: 'z', requirement_letter: 's', reason: "they hiss the same way" },
     // I/L pair - visually similar
-    SoftNoPair { test_letter: 'i', requirement_letter: 'l' },
-    SoftNoPair { test_letter: 'l', requirement_letter: 'i' },
+    SoftNoPair { test_letter: 'i', requirement_letter: 'l', reason: "they look alike" },
+    SoftNoPair { test_letter: 'l', requirement_letter: 'i', reason: "they look alike" },
     // M/N pair - nasals
-    SoftNoPair { test_letter: 'm', requirement_letter: 'n' },
-    SoftNoPair { test_letter: 'n', requirement_letter: 'm' },
+    SoftNoPair { test_letter: 'm', requirement_letter: 'n', reason: "they are both nasal sounds" },
+    SoftNoPair { test_letter: 'n', requirement_letter: 'm', reason: "they are both nasal sounds" },
     // U/V pair - visually similar
-    SoftNoPair { test_letter: 'u', requirement_letter: 'v' },
-    SoftNoPair { test_letter: 'v', requirement_letter: 'u' },
+    SoftNoPair { test_letter: 'u', requirement_letter: 'v', reason: "they look alike" },
+    SoftNoPair { test_letter: 'v', requirement_letter: 'u', reason: "they look alike" },
     // O/Q pair - visually similar
-    SoftNoPair { test_letter: 'o', requirement_letter: 'q' },
-    SoftNoPair { test_letter: 'q', requirement_letter: 'o' },
+    SoftNoPair { test_letter: 'o', requirement_letter: 'q', reason: "they look alike" },
+    SoftNoPair { test_letter: 'q', requirement_letter: 'o', reason: "they look alike" },
     // C/G pair - visually similar
-    SoftNoPair { test_letter: 'c', requirement_letter: 'g' },
-    SoftNoPair { test_letter: 'g', requirement_letter: 'c' },
+    SoftNoPair { test_letter: 'c', requirement_letter: 'g', reason: "they look alike" },
+    SoftNoPair { test_letter: 'g', requirement_letter: 'c', reason: "they look alike" },
     // B/P pair - voiced/unvoiced
-    SoftNoPair { test_letter: 'b', requirement_letter: 'p' },
-    SoftNoPair { test_letter: 'p', requirement_letter: 'b' },
+    SoftNoPair { test_letter: 'b', requirement_letter: 'p', reason: "they are voiced and unvoiced twins" },
+    SoftNoPair { test_letter: 'p', requirement_letter: 'b', reason: "they are voiced and unvoiced twins" },
     // I/T pair - visually similar
-    SoftNoPair { test_letter: 'i', requirement_letter: 't' },
-    SoftNoPair { test_letter: 't', requirement_letter: 'i' },
+    SoftNoPair { test_letter: 'i', requirement_letter: 't', reason: "they look alike" },
+    SoftNoPair { test_letter: 't', requirement_letter: 'i', reason: "they look alike" },
     // R/E pair
-    SoftNoPair { test_letter: 'r', requirement_letter: 'e' },
-    SoftNoPair { test_letter: 'e', requirement_letter: 'r' },
+    SoftNoPair { test_letter: 'r', requirement_letter: 'e', reason: "they share their strokes in block capitals" },
+    SoftNoPair { test_letter: 'e', requirement_letter: 'r', reason: "they share their strokes in block capitals" },
     // A/R pair - similar open shapes in block capitals
-    SoftNoPair { test_letter: 'a', requirement_letter: 'r' },
-    SoftNoPair { test_letter: 'r', requirement_letter: 'a' },
+    SoftNoPair { test_letter: 'a', requirement_letter: 'r', reason: "they have similar open shapes in block capitals" },
+    SoftNoPair { test_letter: 'r', requirement_letter: 'a', reason: "they have similar open shapes in block capitals" },
     // I/J pair
-    SoftNoPair { test_letter: 'i', requirement_letter: 'j' },
-    SoftNoPair { test_letter: 'j', requirement_letter: 'i' },
+    SoftNoPair { test_letter: 'i', requirement_letter: 'j', reason: "they look alike" },
+    SoftNoPair { test_letter: 'j', requirement_letter: 'i', reason: "they look alike" },
     // V/W pair
-    SoftNoPair { test_letter: 'v', requirement_letter: 'w' },
-    SoftNoPair { test_letter: 'w', requirement_letter: 'v' },
+    SoftNoPair { test_letter: 'v', requirement_letter: 'w', reason: "they look alike" },
+    SoftNoPair { test_letter: 'w', requirement_letter: 'v', reason: "they look alike" },
     // Q/G pair
-    SoftNoPair { test_letter: 'q', requirement_letter: 'g' },
-    SoftNoPair { test_letter: 'g', requirement_letter: 'q' },
+    SoftNoPair { test_letter: 'q', requirement_letter: 'g', reason: "they look alike" },
+    SoftNoPair { test_letter: 'g', requirement_letter: 'q', reason: "they look alike" },
     // E/B pair
-    SoftNoPair { test_letter: 'e', requirement_letter: 'b' },
-    SoftNoPair { test_letter: 'b', requirement_letter: 'e' },
+    SoftNoPair { test_letter: 'e', requirement_letter: 'b', reason: "they share their strokes in block capitals" },
+    SoftNoPair { test_letter: 'b', requirement_letter: 'e', reason: "they share their strokes in block capitals" },
     // E/F pair
-    SoftNoPair { test_letter: 'e', requirement_letter: 'f' },
-    SoftNoPair { test_letter: 'f', requirement_letter: 'e' },
+    SoftNoPair { test_letter: 'e', requirement_letter: 'f', reason: "they share their strokes in block capitals" },
+    SoftNoPair { test_letter: 'f', requirement_letter: 'e', reason: "they share their strokes in block capitals" },
     // R/P pair
-    SoftNoPair { test_letter: 'r', requirement_letter: 'p' },
-    SoftNoPair { test_letter: 'p', requirement_letter: 'r' },
+    SoftNoPair { test_letter: 'r', requirement_letter: 'p', reason: "they share their strokes in block capitals" },
+    SoftNoPair { test_letter: 'p', requirement_letter: 'r', reason: "they share their strokes in block capitals" },
     // R/B pair
-    SoftNoPair { test_letter: 'r', requirement_letter: 'b' },
-    SoftNoPair { test_letter: 'b', requirement_letter: 'r' },
+    SoftNoPair { test_letter: 'r', requirement_letter: 'b', reason: "they share their strokes in block capitals" },
+    SoftNoPair { test_letter: 'b', requirement_letter: 'r', reason: "they share their strokes in block capitals" },
     // T/F pair
-    SoftNoPair { test_letter: 't', requirement_letter: 'f' },
-    SoftNoPair { test_letter: 'f', requirement_letter: 't' },
+    SoftNoPair { test_letter: 't', requirement_letter: 'f', reason: "they share their strokes in block capitals" },
+    SoftNoPair { test_letter: 'f', requirement_letter: 't', reason: "they share their strokes in block capitals" },
     // Y/X pair
-    SoftNoPair { test_letter: 'y', requirement_letter: 'x' },
-    SoftNoPair { test_letter: 'x', requirement_letter: 'y' },
+    SoftNoPair { test_letter: 'y', requirement_letter: 'x', reason: "they look alike" },
+    SoftNoPair { test_letter: 'x', requirement_letter: 'y', reason: "they look alike" },
     // Y/V pair
-    SoftNoPair { test_letter: 'y', requirement_letter: 'v' },
-    SoftNoPair { test_letter: 'v', requirement_letter: 'y' },
+    SoftNoPair { test_letter: 'y', requirement_letter: 'v', reason: "they look alike" },
+    SoftNoPair { test_letter: 'v', requirement_letter: 'y', reason: "they look alike" },
     // O/G pair
-    SoftNoPair { test_letter: 'o', requirement_letter: 'g' },
-    SoftNoPair { test_letter: 'g', requirement_letter: 'o' },
+    SoftNoPair { test_letter: 'o', requirement_letter: 'g', reason: "they look alike" },
+    SoftNoPair { test_letter: 'g', requirement_letter: 'o', reason: "they look alike" },
     // P/F pair
-    SoftNoPair { test_letter: 'p', requirement_letter: 'f' },
-    SoftNoPair { test_letter: 'f', requirement_letter: 'p' },
+    SoftNoPair { test_letter: 'p', requirement_letter: 'f', reason: "they sound alike" },
+    SoftNoPair { test_letter: 'f', requirement_letter: 'p', reason: "they sound alike" },
     // A/H pair
-    SoftNoPair { test_letter: 'a', requirement_letter: 'h' },
-    SoftNoPair { test_letter: 'h', requirement_letter: 'a' },
+    SoftNoPair { test_letter: 'a', requirement_letter: 'h', reason: "they share their strokes in block capitals" },
+    SoftNoPair { test_letter: 'h', requirement_letter: 'a', reason: "they share their strokes in block capitals" },
     // D/B pair
-    SoftNoPair { test_letter: 'd', requirement_letter: 'b' },
-    SoftNoPair { test_letter: 'b', requirement_letter: 'd' },
+    SoftNoPair { test_letter: 'd', requirement_letter: 'b', reason: "they look alike" },
+    SoftNoPair { test_letter: 'b', requirement_letter: 'd', reason: "they look alike" },
     // J/L pair
-    SoftNoPair { test_letter: 'j', requirement_letter: 'l' },
-    SoftNoPair { test_letter: 'l', requirement_letter: 'j' },
+    SoftNoPair { test_letter: 'j', requirement_letter: 'l', reason: "they look alike" },
+    SoftNoPair { test_letter: 'l', requirement_letter: 'j', reason: "they look alike" },
 ];
 
+/// Reason given for a soft no pair, if the letters form one.
+pub fn soft_no_reason(test_letter: char, requirement_letter: char) -> Option<&'static str> {
+    SOFT_NO_PAIRS
+        .iter()
+        .find(|pair| pair.test_letter == test_letter && pair.requirement_letter == requirement_letter)
+        .map(|pair| pair.reason)
+}
+
 pub fn split_allowed(
     constraints: &Constraints,
     primary_idx: usize,
@@ -1,8 +1,15 @@
+use std::cell::Cell;
+
 /// Type alias for word bitmasks. u32 supports up to 32 words.
 pub type Mask = u32;
 
+/// Builds the per-letter masks of one question type
+type MaskMaker = fn(&[String]) -> [Mask; 26];
+
 pub struct Context<'a> {
     pub words: &'a [String],
+    /// Alternate spellings of `words[idx]` at index `idx`, possibly shorter than `words`
+    pub aliases: &'a [Vec<String>],
     pub letter_masks: [Mask; 26],
     pub first_letter_masks: [Mask; 26],
     pub second_letter_masks: [Mask; 26],
@@ -12,32 +19,132 @@ pub struct Context<'a> {
     pub third_to_last_letter_masks: [Mask; 26],
     pub double_letter_masks: [Mask; 26],
     pub triple_letter_masks: [Mask; 26],
+    /// Words whose spellings disagree on a question, indexed by `Position as usize`;
+    /// the masks above only hold words for which every spelling answers Yes
+    pub mixed_masks: [[Mask; 26]; 9],
     pub global_letters: Vec<usize>, // Precomputed letters present in word set
+    /// Subproblems the solver may still expand before giving up, `None` for no limit
+    steps_left: Cell<Option<u64>>,
+    /// Set when the solver needed a step after they ran out
+    interrupted: Cell<bool>,
+    /// Cleared to finish every split whatever its No branch costs, to check that pruning on
+    /// the No branch never changes the result
+    prunes_no_branches: Cell<bool>,
 }
 
 impl<'a> Context<'a> {
     pub fn new(words: &'a [String]) -> Self {
-        let letter_masks = make_letter_masks(words);
+        Self::with_aliases(words, &[])
+    }
+
+    /// Treat each word as the set of its spellings: the word and `aliases[idx]`
+    pub fn with_aliases(words: &'a [String], aliases: &'a [Vec<String>]) -> Self {
+        // Same order as `Position`
+        let makers: [MaskMaker; 9] = [
+            make_letter_masks,
+            make_first_letter_masks,
+            make_second_letter_masks,
+            make_third_letter_masks,
+            make_third_to_last_letter_masks,
+            make_second_to_last_letter_masks,
+            make_last_letter_masks,
+            make_double_letter_masks,
+            make_triple_letter_masks,
+        ];
+        let mut all_masks = [[0 as Mask; 26]; 9];
+        let mut mixed_masks = [[0 as Mask; 26]; 9];
+        for (position, make) in makers.iter().enumerate() {
+            all_masks[position] = make(words);
+            if aliases.iter().all(Vec::is_empty) {
+                continue;
+            }
+            for (idx, spellings) in aliases.iter().enumerate().take(words.len()) {
+                for spelling in spellings {
+                    let masks = make(std::slice::from_ref(spelling));
+                    for letter in 0..26 {
+                        let bit = (1 as Mask) << idx;
+                        if (all_masks[position][letter] & bit != 0) != (masks[letter] != 0) {
+                            all_masks[position][letter] &= !bit;
+                            mixed_masks[position][letter] |= bit;
+                        }
+                    }
+                }
+            }
+        }
+
         let mut global_letters = Vec::with_capacity(26);
-        for (idx, &mask) in letter_masks.iter().enumerate() {
+        for (idx, &mask) in all_masks[0].iter().enumerate() {
             if mask != 0 {
                 global_letters.push(idx);
             }
         }
+        let [letter_masks, first, second, third, third_to_last, second_to_last, last, double, triple] = all_masks;
         Context {
             words,
+            aliases,
             letter_masks,
-            first_letter_masks: make_first_letter_masks(words),
-            second_letter_masks: make_second_letter_masks(words),
-            third_letter_masks: make_third_letter_masks(words),
-            last_letter_masks: make_last_letter_masks(words),
-            second_to_last_letter_masks: make_second_to_last_letter_masks(words),
-            third_to_last_letter_masks: make_third_to_last_letter_masks(words),
-            double_letter_masks: make_double_letter_masks(words),
-            triple_letter_masks: make_triple_letter_masks(words),
+            first_letter_masks: first,
+            second_letter_masks: second,
+            third_letter_masks: third,
+            last_letter_masks: last,
+            second_to_last_letter_masks: second_to_last,
+            third_to_last_letter_masks: third_to_last,
+            double_letter_masks: double,
+            triple_letter_masks: triple,
+            mixed_masks,
             global_letters,
+            steps_left: Cell::new(None),
+            interrupted: Cell::new(false),
+            prunes_no_branches: Cell::new(true),
         }
     }
+
+    /// Let the solver expand at most `steps` new subproblems (memo misses) from now on.
+    /// Once they run out it stops early and stops memoizing, so an interrupted search
+    /// leaves only complete entries behind and can be resumed with the same memo.
+    pub fn limit_steps(&self, steps: Option<u64>) {
+        self.steps_left.set(steps);
+        self.interrupted.set(false);
+    }
+
+    /// Steps left under the current limit, `None` without a limit
+    pub fn steps_left(&self) -> Option<u64> {
+        self.steps_left.get()
+    }
+
+    /// Whether the solver ran out of steps, making any result since then meaningless
+    pub fn interrupted(&self) -> bool {
+        self.interrupted.get()
+    }
+
+    pub(crate) fn prunes_no_branches(&self) -> bool {
+        self.prunes_no_branches.get()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn disable_no_branch_pruning(&self) {
+        self.prunes_no_branches.set(false);
+    }
+
+    /// Spend one step, returning false (and marking the search interrupted) if none were left
+    pub(crate) fn take_step(&self) -> bool {
+        match self.steps_left.get() {
+            None => true,
+            Some(0) => {
+                self.interrupted.set(true);
+                false
+            }
+            Some(left) => {
+                self.steps_left.set(Some(left - 1));
+                true
+            }
+        }
+    }
+
+    /// Every spelling of `words[idx]`, the word itself first
+    pub fn spellings(&self, idx: usize) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.words[idx]).chain(self.aliases.get(idx).into_iter().flatten())
+    }
 }
 
 pub const fn mask_count(mask: Mask) -> u32 {
@@ -101,10 +208,11 @@ pub const fn partitions<'a>(
     Partitions { masks, mask, global_letters, idx: 0 }
 }
 
+/// Letters in any spelling of the words in `mask`
 pub fn letters_present(mask: Mask, ctx: &Context<'_>) -> u32 {
     let mut present: u32 = 0;
     for idx in 0..26 {
-        if mask & ctx.letter_masks[idx] != 0 {
+        if mask & (ctx.letter_masks[idx] | ctx.mixed_masks[0][idx]) != 0 {
             present |= 1u32 << idx;
         }
     }
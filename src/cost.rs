@@ -1,7 +1,8 @@
 use std::cmp::Ordering;
+use serde::{Deserialize, Serialize};
 use crate::context::{Mask, mask_count};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub struct Cost {
     /// Number of hard No-edges on the heaviest path (primary objective).
     pub hard_nos: u32,
@@ -19,6 +20,15 @@ pub struct Cost {
     pub sum_nos: u32,
     /// Redeemed sum of No-edges (scaled by `redeeming_yes` parameter).
     pub redeemed_sum_nos: i32,
+    /// Longest run of consecutive No edges on any path.
+    pub max_consecutive_nos: u32,
+    /// Longest run of No edges starting at the root of this subtree
+    /// (needed to extend runs when the subtree hangs below a No edge).
+    pub leading_nos: u32,
+    /// Number of questions (Yes or No, including `YesSplit` and Repeat) on the longest path.
+    pub max_questions: u32,
+    /// Sum of questions weighted by word count.
+    pub sum_questions: u32,
     /// Number of words in this subtree.
     pub word_count: u32,
 }
@@ -36,6 +46,10 @@ pub fn add_no_edge(base: &Cost, is_hard: bool, redeeming_yes: i32) -> Cost {
             redeemed_sum_hard_nos: base.redeemed_sum_hard_nos,
             sum_nos: base.sum_nos,
             redeemed_sum_nos: base.redeemed_sum_nos,
+            max_consecutive_nos: base.max_consecutive_nos.max(base.leading_nos + 1),
+            leading_nos: base.leading_nos + 1,
+            max_questions: base.max_questions,
+            sum_questions: base.sum_questions,
             word_count: base.word_count,
         }
     } else {
@@ -48,13 +62,39 @@ pub fn add_no_edge(base: &Cost, is_hard: bool, redeeming_yes: i32) -> Cost {
             redeemed_sum_hard_nos: base.redeemed_sum_hard_nos,
             sum_nos: base.sum_nos,
             redeemed_sum_nos: base.redeemed_sum_nos,
+            max_consecutive_nos: base.max_consecutive_nos.max(base.leading_nos + 1),
+            leading_nos: base.leading_nos + 1,
+            max_questions: base.max_questions,
+            sum_questions: base.sum_questions,
             word_count: base.word_count,
         }
     }
 }
 
-/// Apply the cost impact of a `YesSplit`.
-/// `YesSplit`s contribute -1 to all `redeemed_*` metrics (not scaled by `redeeming_yes`).
+/// Add the No edge of a Repeat above its (solved) No branch, sums included. The edge is free
+/// unless `no_edge` says whether it counts as a hard or soft No (see `RepeatCost::no_edge`).
+pub fn add_repeat_edge(no: &Cost, no_edge: Option<bool>, redeeming_yes: i32) -> Cost {
+    let Some(is_hard) = no_edge else {
+        return *no;
+    };
+    let mut cost = add_no_edge(no, is_hard, redeeming_yes);
+    cost.sum_nos += no.word_count;
+    cost.redeemed_sum_nos += no.word_count as i32 * redeeming_yes;
+    if is_hard {
+        cost.sum_hard_nos += no.word_count;
+        cost.redeemed_sum_hard_nos += no.word_count as i32 * redeeming_yes;
+    }
+    cost.redeemed_hard_nos = cost.redeemed_hard_nos.max(0);
+    cost.redeemed_nos = cost.redeemed_nos.max(0);
+    cost.redeemed_sum_hard_nos = cost.redeemed_sum_hard_nos.max(0);
+    cost.redeemed_sum_nos = cost.redeemed_sum_nos.max(0);
+    cost
+}
+
+/// Apply the cost impact of a `YesSplit` (or of the Yes edge of a split).
+/// `YesSplit`s contribute -1 to all `redeemed_*` metrics (not scaled by `redeeming_yes`)
+/// and end any run of consecutive No edges.
+/// The question they ask is counted separately, with `add_questions`.
 pub fn add_yes_split(base: &Cost) -> Cost {
     Cost {
         hard_nos: base.hard_nos,
@@ -65,24 +105,52 @@ pub fn add_yes_split(base: &Cost) -> Cost {
         redeemed_sum_hard_nos: base.redeemed_sum_hard_nos - 1,
         sum_nos: base.sum_nos,
         redeemed_sum_nos: base.redeemed_sum_nos - 1,
+        max_consecutive_nos: base.max_consecutive_nos,
+        leading_nos: 0,
+        max_questions: base.max_questions,
+        sum_questions: base.sum_questions,
         word_count: base.word_count,
     }
 }
 
+/// Add `count` questions on top of every path of a subtree.
+/// Used for the question of a split (on both branches), a `YesSplit` or a Repeat.
+pub fn add_questions(base: &Cost, count: u32) -> Cost {
+    Cost {
+        max_questions: base.max_questions + count,
+        sum_questions: base.sum_questions + count * base.word_count,
+        ..*base
+    }
+}
+
+/// Lower bounds on the questions needed to tell `count` words apart with yes/no questions:
+/// the depth and the external path length of a balanced binary tree.
+pub const fn min_questions(count: u32) -> (u32, u32) {
+    if count <= 1 {
+        return (0, 0);
+    }
+    let depth = u32::BITS - (count - 1).leading_zeros();
+    let full_levels = u32::BITS - 1 - count.leading_zeros();
+    (depth, count * full_levels + 2 * (count - (1 << full_levels)))
+}
+
 /// Estimate lower bound cost for a state (used for candidate ordering).
 /// This provides an optimistic (lower) bound that guarantees we won't prune optimal solutions.
-pub fn estimate_cost(mask: Mask, allow_repeat: bool, redeeming_yes: u32) -> Cost {
+pub fn estimate_cost(mask: Mask, free_repeats: u32, redeeming_yes: u32) -> Cost {
     // Lower bounds:
     // - nos: 1 if N >= threshold, else 0
-    //   - When allow_repeat=true: threshold is 3 (2 words can be handled with Repeat, nos=0)
-    //   - When allow_repeat=false: threshold is 2 (need at least one split)
+    //   - threshold is free_repeats + 2 (a chain of k free Repeats handles k + 1 words, nos=0)
+    //   - without free Repeats: threshold is 2 (need at least one split)
     // - hard_nos: 0 (optimistic: assume all soft splits)
     // - sum_nos: N-1 (balanced tree has N-1 internal nodes, each adds ≥1)
     // - sum_hard_nos: 0 (optimistic: assume all soft)
+    // - max_consecutive_nos: same as nos (the all-No path ends with a run of at least one)
+    // - max_questions / sum_questions: depth and external path length of a balanced tree
     let count: u32 = mask_count(mask);
-    let threshold = if allow_repeat { 3 } else { 2 };
+    let threshold = free_repeats + 2;
     let nos_estimate = if count >= threshold { 1 } else { 0 };
     let sum_nos_estimate = count.saturating_sub(1);
+    let (max_questions, sum_questions) = min_questions(count);
 
     // the `nos_estimate * redeeming_yes` redemed costs are actualy pessimistic, but necessary to avoid paths explosions
     Cost {
@@ -94,10 +162,151 @@ pub fn estimate_cost(mask: Mask, allow_repeat: bool, redeeming_yes: u32) -> Cost
         redeemed_sum_hard_nos: 0,
         sum_nos: sum_nos_estimate,
         redeemed_sum_nos: (sum_nos_estimate * redeeming_yes) as i32,
+        max_consecutive_nos: nos_estimate,
+        leading_nos: 0,
+        max_questions,
+        sum_questions,
         word_count: count,
     }
 }
 
+/// Weights for the scalar cost mode.
+///
+/// A path with `h` hard and `s` soft No edges is worth `hard_no * h + soft_no * s`. The max part
+/// weighs the most No edges on any path by `soft_no` and the most hard ones by the difference,
+/// and the two can come from different paths. It is then an upper bound on the heaviest path:
+/// 3 soft Nos on one path and 1 hard No on another score 5 at the defaults, though no path is
+/// worth more than 3. The max part is scaled by `max` and the per-word average by `sum`.
+/// Every metric is read as `raw + redemption * redeemed`, so `redemption = 0` ignores `YesSplit`s.
+/// Questions (of any answer) are weighted by `question`, with the same `max` / `sum` split.
+/// All weights must be non-negative, and `hard_no` below `soft_no` is treated as `soft_no`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CostWeights {
+    /// Weight of a hard No edge.
+    pub hard_no: f64,
+    /// Weight of a soft No edge.
+    pub soft_no: f64,
+    /// Weight of the max part (the per-path maxima, see above).
+    pub max: f64,
+    /// Weight of the average path (sum metrics divided by word count).
+    pub sum: f64,
+    /// Weight of the redeemed metrics relative to the raw ones.
+    pub redemption: f64,
+    /// Weight of a question, whatever its answer.
+    pub question: f64,
+}
+
+impl Default for CostWeights {
+    fn default() -> Self {
+        CostWeights { hard_no: 3.0, soft_no: 1.0, max: 1.0, sum: 1.0, redemption: 1.0, question: 0.0 }
+    }
+}
+
+/// Scalar objective combining the metrics of `cost` with user weights (lower is better).
+/// Like `compare_costs`, it reads the per-path maxima of `Cost` one metric at a time, so each
+/// subproblem keeps the trees with the best scalar of their own, not of the heaviest path.
+pub fn weighted_cost(cost: &Cost, weights: &CostWeights) -> f64 {
+    // A hard No is also a No, so it only adds the difference on top of `nos`
+    let extra_hard = (weights.hard_no - weights.soft_no).max(0.0);
+    let metric = |raw: u32, redeemed: i32| raw as f64 + weights.redemption * redeemed as f64;
+    let word_count = cost.word_count.max(1) as f64;
+
+    let max_part = weights.soft_no * metric(cost.nos, cost.redeemed_nos)
+                   + extra_hard * metric(cost.hard_nos, cost.redeemed_hard_nos)
+                   + weights.question * cost.max_questions as f64;
+    let sum_part = (weights.soft_no * metric(cost.sum_nos, cost.redeemed_sum_nos)
+                    + extra_hard * metric(cost.sum_hard_nos, cost.redeemed_sum_hard_nos)
+                    + weights.question * cost.sum_questions as f64)
+                   / word_count;
+
+    weights.max * max_part + weights.sum * sum_part
+}
+
+/// Compare costs by their weighted scalar, falling back to `compare_costs` on ties
+/// so that equally weighted trees always share the same `Cost`.
+pub fn compare_weighted_costs(a: &Cost, b: &Cost, weights: &CostWeights, prioritize_soft_no: bool) -> Ordering {
+    weighted_cost(a, weights)
+        .total_cmp(&weighted_cost(b, weights))
+        .then_with(|| compare_costs(a, b, prioritize_soft_no))
+}
+
+/// Admissible counterpart of `estimate_cost`: every field is a true lower bound.
+///
+/// The all-No path of any tree ends with a No edge into a single word, which no `YesSplit`
+/// can redeem, so `nos` and `redeemed_nos` bounds match `estimate_cost`. Only the words met
+/// on the path of Yes edges and free Repeat No edges from the root avoid a No, and `k` free
+/// Repeats per path leave `k + 1` of them, so sums use N-k-1.
+///
+/// `YesSplit`s lower the redeemed sums too, by at most `redeeming_yes` per No edge, and only
+/// below an edge leading to more words than its subtree's Yes/free-Repeat path can hold. Such
+/// a subtree has a word with a second No edge below it, whose first No edge leaving that path
+/// belongs to no other such edge. It adds `redeeming_yes` more than the N-k-1 bound counts,
+/// which covers the redemption, so `redeemed_sum_nos` keeps `(N-k-1) * redeeming_yes`.
+pub fn admissible_estimate_cost(mask: Mask, free_repeats: u32, redeeming_yes: u32) -> Cost {
+    let count: u32 = mask_count(mask);
+    let threshold = free_repeats + 2;
+    let nos_estimate = if count >= threshold { 1 } else { 0 };
+    let sum_nos_estimate = count.saturating_sub(free_repeats + 1);
+    let (max_questions, sum_questions) = min_questions(count);
+
+    Cost {
+        hard_nos: 0,
+        redeemed_hard_nos: 0,
+        nos: nos_estimate,
+        redeemed_nos: (nos_estimate * redeeming_yes) as i32,
+        sum_hard_nos: 0,
+        redeemed_sum_hard_nos: 0,
+        sum_nos: sum_nos_estimate,
+        redeemed_sum_nos: (sum_nos_estimate * redeeming_yes) as i32,
+        max_consecutive_nos: nos_estimate,
+        leading_nos: 0,
+        max_questions,
+        sum_questions,
+        word_count: count,
+    }
+}
+
+/// Admissible estimate of a split with the given branches.
+/// Unlike the lexicographic candidate estimate, this accounts for the `YesSplit`s that may
+/// redeem the new No edge and for the Yes edge redemption applied to the Yes branch.
+pub fn admissible_split_estimate(yes: Mask, no: Mask, is_hard: bool, free_repeats: u32, redeeming_yes: u32) -> Cost {
+    let est_yes = admissible_estimate_cost(yes, free_repeats, redeeming_yes);
+    admissible_split_bound(&est_yes, no, is_hard, free_repeats, redeeming_yes)
+}
+
+/// `admissible_split_estimate` with the Yes branch known to cost at least `est_yes`, such as
+/// its solved cost. The No branch is still estimated from its words alone, so the bound holds
+/// whatever budget and constraints the `YesSplit`s leave it.
+pub fn admissible_split_bound(est_yes: &Cost, no: Mask, is_hard: bool, free_repeats: u32, redeeming_yes: u32) -> Cost {
+    let est_no = admissible_estimate_cost(no, free_repeats, redeeming_yes);
+    let redemption = redeeming_yes as i32;
+    let min_words_for_yes_split = free_repeats + 2;
+    let max_yes_splits = if est_no.word_count >= min_words_for_yes_split { redemption } else { 0 };
+    let hard = u32::from(is_hard);
+    let hard_redemption = if is_hard { redemption } else { 0 };
+
+    Cost {
+        hard_nos: est_yes.hard_nos.max(est_no.hard_nos + hard),
+        redeemed_hard_nos: (est_yes.redeemed_hard_nos - 1)
+            .max(est_no.redeemed_hard_nos + hard_redemption - max_yes_splits)
+            .max(0),
+        nos: est_yes.nos.max(est_no.nos + 1),
+        redeemed_nos: (est_yes.redeemed_nos - 1).max(est_no.redeemed_nos + redemption - max_yes_splits).max(0),
+        sum_hard_nos: est_yes.sum_hard_nos + est_no.sum_hard_nos + hard * est_no.word_count,
+        redeemed_sum_hard_nos: est_yes.redeemed_sum_hard_nos
+            + (est_no.redeemed_sum_hard_nos + hard_redemption * est_no.word_count as i32 - max_yes_splits).max(0),
+        sum_nos: est_yes.sum_nos + est_no.sum_nos + est_no.word_count,
+        redeemed_sum_nos: est_yes.redeemed_sum_nos
+            + (est_no.redeemed_sum_nos + redemption * est_no.word_count as i32 - max_yes_splits).max(0),
+        max_consecutive_nos: est_yes.max_consecutive_nos.max(est_no.max_consecutive_nos).max(1),
+        leading_nos: 1,
+        max_questions: 1 + est_yes.max_questions.max(est_no.max_questions),
+        sum_questions: est_yes.sum_questions + est_no.sum_questions + est_yes.word_count + est_no.word_count,
+        word_count: est_yes.word_count + est_no.word_count,
+    }
+}
+
 pub fn compare_costs(a: &Cost, b: &Cost, prioritize_soft_no: bool) -> Ordering {
     if prioritize_soft_no {
         a.redeemed_hard_nos
@@ -125,6 +334,8 @@ pub fn compare_costs(a: &Cost, b: &Cost, prioritize_soft_no: bool) -> Ordering {
                 let right = (b.sum_nos as u64) * (a.word_count as u64);
                 left.cmp(&right)
             })
+            .then_with(|| compare_runs(a, b))
+            .then_with(|| compare_questions(a, b))
     } else {
         a.redeemed_nos
             .cmp(&b.redeemed_nos)
@@ -151,5 +362,22 @@ pub fn compare_costs(a: &Cost, b: &Cost, prioritize_soft_no: bool) -> Ordering {
                 let right = (b.sum_hard_nos as u64) * (a.word_count as u64);
                 left.cmp(&right)
             })
+            .then_with(|| compare_runs(a, b))
+            .then_with(|| compare_questions(a, b))
     }
 }
+
+/// Tie-break shared by both modes: the shorter longest run of Nos, then the shorter leading
+/// run. A subtree below a No edge extends its leading run, so this order is the parent's.
+fn compare_runs(a: &Cost, b: &Cost) -> Ordering {
+    a.max_consecutive_nos.cmp(&b.max_consecutive_nos).then_with(|| a.leading_nos.cmp(&b.leading_nos))
+}
+
+/// Tie-break shared by both modes: fewer questions on the longest path, then on average.
+fn compare_questions(a: &Cost, b: &Cost) -> Ordering {
+    a.max_questions.cmp(&b.max_questions).then_with(|| {
+        let left = (a.sum_questions as u64) * (b.word_count as u64);
+        let right = (b.sum_questions as u64) * (a.word_count as u64);
+        left.cmp(&right)
+    })
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use hashbrown::HashMap;
+
+use crate::api::solve_subset;
+use crate::config::SolverConfig;
+use crate::context::{mask_count, Context, Mask};
+use crate::node::Solution;
+
+/// What to pick from a candidate pool in `design_subsets`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesignOptions {
+    /// Number of words in each subset, including the required ones
+    pub size: usize,
+    /// Words every subset must contain
+    pub required: Vec<String>,
+    /// Number of subsets to report
+    pub top: usize,
+    /// Most subsets to search; larger searches fail up front with `TooManyCombinations`
+    pub max_combinations: u64,
+}
+
+impl DesignOptions {
+    /// Default `max_combinations`
+    pub const MAX_COMBINATIONS: u64 = 100_000;
+
+    /// Subsets of `size` words, no required words, top five reported
+    pub const fn new(size: usize) -> Self {
+        DesignOptions { size, required: Vec::new(), top: 5, max_combinations: Self::MAX_COMBINATIONS }
+    }
+}
+
+/// One subset of the pool with its optimal trees
+#[derive(Debug, Clone)]
+pub struct DesignCandidate {
+    /// The chosen words, in pool order
+    pub words: Vec<String>,
+    pub solution: Solution,
+}
+
+/// Returned when the pool and options do not describe a valid search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DesignError {
+    /// The solver's bitmasks hold at most 32 words.
+    PoolTooLarge(usize),
+    DuplicateWord(String),
+    /// A required word is not in the pool.
+    UnknownRequired(String),
+    /// The size is zero, below the number of required words or above the pool size.
+    InvalidSize { size: usize, required: usize, pool: usize },
+    /// The pool and size give more subsets than `max_combinations` allows.
+    TooManyCombinations { count: u64, limit: u64 },
+}
+
+impl fmt::Display for DesignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DesignError::PoolTooLarge(count) => write!(f, "the pool has {count} words, the solver supports up to 32"),
+            DesignError::DuplicateWord(word) => write!(f, "'{word}' appears more than once in the pool"),
+            DesignError::UnknownRequired(word) => write!(f, "required word '{word}' is not in the pool"),
+            DesignError::InvalidSize { size, required, pool } => {
+                write!(f, "cannot pick {size} words from a pool of {pool} with {required} required")
+            }
+            DesignError::TooManyCombinations { count, limit } => {
+                write!(f, "{count} subsets to search, more than the limit of {limit}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DesignError {}
+
+/// Number of ways to pick `count` of `total` items (`total` is at most 32, so it fits)
+fn combinations(total: usize, count: usize) -> u64 {
+    (0..count as u64).fold(1, |acc, idx| acc * (total as u64 - idx) / (idx + 1))
+}
+
+/// Calls `visit` with every mask made of `base` plus `count` of the bits in `optional[start..]`
+fn for_each_combination(optional: &[Mask], start: usize, count: usize, base: Mask, visit: &mut impl FnMut(Mask)) {
+    if count == 0 {
+        visit(base);
+        return;
+    }
+    for idx in start..=optional.len().saturating_sub(count) {
+        for_each_combination(optional, idx + 1, count - 1, base | optional[idx], visit);
+    }
+}
+
+/// Search every subset of `pool` with `options.size` words including `options.required`,
+/// and return the `options.top` best by `config`, best first (ties keep pool order).
+/// Fails without searching when there are more than `options.max_combinations` subsets.
+/// Subsets are solved as masks over the whole pool, so they all share one memo.
+pub fn design_subsets(
+    pool: &[String],
+    options: &DesignOptions,
+    config: &SolverConfig,
+) -> Result<Vec<DesignCandidate>, DesignError> {
+    if pool.len() > 32 {
+        return Err(DesignError::PoolTooLarge(pool.len()));
+    }
+    for (idx, word) in pool.iter().enumerate() {
+        if pool[..idx].contains(word) {
+            return Err(DesignError::DuplicateWord(word.clone()));
+        }
+    }
+    let mut required: Mask = 0;
+    for word in &options.required {
+        let idx = pool.iter().position(|w| w == word).ok_or_else(|| DesignError::UnknownRequired(word.clone()))?;
+        required |= 1 << idx;
+    }
+    let required_count = mask_count(required) as usize;
+    if options.size == 0 || options.size < required_count || options.size > pool.len() {
+        return Err(DesignError::InvalidSize { size: options.size, required: required_count, pool: pool.len() });
+    }
+    let count = combinations(pool.len() - required_count, options.size - required_count);
+    if count > options.max_combinations {
+        return Err(DesignError::TooManyCombinations { count, limit: options.max_combinations });
+    }
+
+    let ctx = Context::new(pool);
+    let optional: Vec<Mask> = (0..pool.len()).map(|idx| 1 << idx).filter(|bit| required & bit == 0).collect();
+    let mut memo = HashMap::new();
+    let mut best: Vec<(Mask, Solution)> = Vec::new();
+    for_each_combination(&optional, 0, options.size - required_count, required, &mut |mask| {
+        let sol = solve_subset(&ctx, mask, config, config.max_depth, &mut memo);
+        if sol.is_unsolvable() {
+            return;
+        }
+        let rank = best.partition_point(|(_, other)| config.compare(&other.cost, &sol.cost).is_le());
+        if rank < options.top {
+            best.insert(rank, (mask, sol));
+            best.truncate(options.top);
+        }
+    });
+
+    Ok(best
+        .into_iter()
+        .map(|(mask, solution)| DesignCandidate {
+            words: pool.iter().enumerate().filter(|(idx, _)| mask & (1 << idx) != 0).map(|(_, w)| w.clone()).collect(),
+            solution,
+        })
+        .collect())
+}
@@ -0,0 +1,201 @@
+use std::fmt::Write;
+
+use crate::format::{capitalize_first, format_position_question};
+use crate::merged::{MergedNode, NodeInfo};
+use crate::node::{is_hard_split, Node};
+
+/// Kind of a diagram node, which decides its shape
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Leaf,
+    Split,
+    YesSplit,
+    Repeat,
+    /// Several optimal options in a `MergedNode`
+    Choice,
+}
+
+/// Kind of a diagram edge, which decides its label and style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edge {
+    Yes,
+    HardNo,
+    /// Soft No edges, and the No edge of a Repeat (drawn soft whatever its `RepeatCost`)
+    SoftNo,
+    /// Edge from a choice to its n-th option (1-based)
+    Option(usize),
+}
+
+/// Renderer-agnostic graph, built from a `Node` or a `MergedNode`
+#[derive(Default)]
+struct Graph {
+    nodes: Vec<(String, Shape)>,
+    edges: Vec<(usize, usize, Edge)>,
+}
+
+impl Graph {
+    fn add_node(&mut self, label: String, shape: Shape) -> usize {
+        self.nodes.push((label, shape));
+        self.nodes.len() - 1
+    }
+
+    /// Add the node described by `info`, returning its id and the style of its No edge
+    fn add_info(&mut self, info: &NodeInfo) -> (usize, Edge) {
+        match info {
+            NodeInfo::Leaf { word } => (self.add_node(capitalize_first(word), Shape::Leaf), Edge::SoftNo),
+            NodeInfo::Repeat { word } => {
+                (self.add_node(format!("Repeat {}", capitalize_first(word)), Shape::Repeat), Edge::SoftNo)
+            }
+            NodeInfo::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position } => {
+                let label =
+                    format_position_question(*test_letter, test_position, *requirement_letter, requirement_position);
+                let hard = is_hard_split(*test_letter, *test_position, *requirement_letter, *requirement_position);
+                (self.add_node(label, Shape::Split), if hard { Edge::HardNo } else { Edge::SoftNo })
+            }
+            NodeInfo::YesSplit { test_letter, test_position, requirement_letter, requirement_position } => {
+                let question =
+                    format_position_question(*test_letter, test_position, *requirement_letter, requirement_position);
+                (self.add_node(format!("{question} (yes only)"), Shape::YesSplit), Edge::SoftNo)
+            }
+        }
+    }
+
+    fn add_tree(&mut self, node: &Node) -> usize {
+        let (id, no_edge) = self.add_info(&NodeInfo::from_node(node));
+        match node {
+            Node::Leaf(_) => {}
+            Node::Repeat { word, no } => {
+                let yes_id = self.add_tree(&Node::Leaf(word.clone()));
+                self.edges.push((id, yes_id, Edge::Yes));
+                let no_id = self.add_tree(no);
+                self.edges.push((id, no_id, no_edge));
+            }
+            Node::PositionalSplit { yes, no, .. } => {
+                let yes_id = self.add_tree(yes);
+                self.edges.push((id, yes_id, Edge::Yes));
+                let no_id = self.add_tree(no);
+                self.edges.push((id, no_id, no_edge));
+            }
+            Node::YesSplit { yes, .. } => {
+                let yes_id = self.add_tree(yes);
+                self.edges.push((id, yes_id, Edge::Yes));
+            }
+        }
+        id
+    }
+
+    fn add_merged(&mut self, merged: &MergedNode) -> Option<usize> {
+        let mut option_ids = Vec::with_capacity(merged.options.len());
+        for option in &merged.options {
+            let (id, no_edge) = self.add_info(&option.info);
+            // Merged Repeats only store their No branch; the Yes branch is the repeated word
+            if let NodeInfo::Repeat { word } = &option.info {
+                let yes_id = self.add_node(capitalize_first(word), Shape::Leaf);
+                self.edges.push((id, yes_id, Edge::Yes));
+            }
+            if let Some(yes_id) = option.yes_branch.as_deref().and_then(|yes| self.add_merged(yes)) {
+                self.edges.push((id, yes_id, Edge::Yes));
+            }
+            if let Some(no_id) = option.no_branch.as_deref().and_then(|no| self.add_merged(no)) {
+                self.edges.push((id, no_id, no_edge));
+            }
+            option_ids.push(id);
+        }
+
+        if option_ids.len() <= 1 {
+            return option_ids.first().copied();
+        }
+        let choice = self.add_node(format!("{} options", option_ids.len()), Shape::Choice);
+        for (idx, id) in option_ids.into_iter().enumerate() {
+            self.edges.push((choice, id, Edge::Option(idx + 1)));
+        }
+        Some(choice)
+    }
+
+    fn to_dot(&self) -> String {
+        let mut out = String::from("digraph tree {\n    node [fontname=\"Helvetica\"];\n");
+        for (id, (label, shape)) in self.nodes.iter().enumerate() {
+            let attrs = match shape {
+                Shape::Leaf => "shape=ellipse, style=filled, fillcolor=\"#e8f5e9\"",
+                Shape::Split => "shape=box",
+                Shape::YesSplit => "shape=box, style=\"rounded,dashed\"",
+                Shape::Repeat => "shape=hexagon, style=filled, fillcolor=\"#e3f2fd\"",
+                Shape::Choice => "shape=diamond, style=filled, fillcolor=\"#fff3e0\"",
+            };
+            let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+            let _ = writeln!(out, "    n{id} [label=\"{label}\", {attrs}];");
+        }
+        for (from, to, edge) in &self.edges {
+            let attrs = match edge {
+                Edge::Yes => "label=\"Yes\", color=\"#2e7d32\"".to_string(),
+                Edge::HardNo => "label=\"No\", color=\"#c62828\", penwidth=2".to_string(),
+                Edge::SoftNo => "label=\"No\", color=\"#ef6c00\", style=dashed".to_string(),
+                Edge::Option(n) => format!("label=\"option {n}\", color=\"#9e9e9e\", style=dotted"),
+            };
+            let _ = writeln!(out, "    n{from} -> n{to} [{attrs}];");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart TD\n");
+        for (id, (label, shape)) in self.nodes.iter().enumerate() {
+            let label = label.replace('"', "#quot;");
+            let node = match shape {
+                Shape::Leaf => format!("n{id}([\"{label}\"])"),
+                Shape::Split => format!("n{id}[\"{label}\"]"),
+                Shape::YesSplit => format!("n{id}[/\"{label}\"/]"),
+                Shape::Repeat => format!("n{id}{{{{\"{label}\"}}}}"),
+                Shape::Choice => format!("n{id}{{\"{label}\"}}"),
+            };
+            let _ = writeln!(out, "    {node}");
+        }
+        for (from, to, edge) in &self.edges {
+            let arrow = match edge {
+                Edge::Yes => "-->|Yes|".to_string(),
+                Edge::HardNo => "==>|No|".to_string(),
+                Edge::SoftNo => "-.->|No|".to_string(),
+                Edge::Option(n) => format!("---|option {n}|"),
+            };
+            let _ = writeln!(out, "    n{from} {arrow} n{to}");
+        }
+        out
+    }
+}
+
+impl Node {
+    /// Render this tree as a Graphviz DOT digraph.
+    /// Hard No edges are bold red, soft No edges dashed orange; `YesSplit`s are dashed boxes
+    /// and Repeats hexagons.
+    pub fn to_dot(&self) -> String {
+        let mut graph = Graph::default();
+        graph.add_tree(self);
+        graph.to_dot()
+    }
+
+    /// Render this tree as a Mermaid flowchart, styled like `to_dot`
+    /// (hard No edges thick, soft No edges dotted).
+    pub fn to_mermaid(&self) -> String {
+        let mut graph = Graph::default();
+        graph.add_tree(self);
+        graph.to_mermaid()
+    }
+}
+
+impl MergedNode {
+    /// Render all merged optimal trees as a Graphviz DOT digraph, with a diamond wherever
+    /// the trees disagree leading to each option.
+    pub fn to_dot(&self) -> String {
+        let mut graph = Graph::default();
+        graph.add_merged(self);
+        graph.to_dot()
+    }
+
+    /// Render all merged optimal trees as a Mermaid flowchart, styled like `to_dot`.
+    pub fn to_mermaid(&self) -> String {
+        let mut graph = Graph::default();
+        graph.add_merged(self);
+        graph.to_mermaid()
+    }
+}
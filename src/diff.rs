@@ -0,0 +1,249 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::config::RepeatCost;
+use crate::format::{capitalize_first, format_path, format_question};
+use crate::merged::{child, Branch, NodeInfo};
+use crate::node::Node;
+use crate::report::{word_paths, WordPath};
+
+/// A node that asks something else than the node at the same path of the other tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedQuestion {
+    pub path: Vec<Branch>,
+    pub before: NodeInfo,
+    pub after: NodeInfo,
+}
+
+/// A subtree (more than a single word) that both trees contain, at different paths
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovedSubtree {
+    pub from: Vec<Branch>,
+    pub to: Vec<Branch>,
+    /// Words reached inside the subtree
+    pub words: Vec<String>,
+}
+
+/// What the performer asks on the way to one word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordStats {
+    pub questions: u32,
+    pub nos: u32,
+    pub hard_nos: u32,
+}
+
+impl From<&WordPath> for WordStats {
+    fn from(path: &WordPath) -> Self {
+        WordStats { questions: path.questions(), nos: path.nos, hard_nos: path.hard_nos }
+    }
+}
+
+/// A word in both trees whose path differs in length or No answers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordChange {
+    pub word: String,
+    pub before: WordStats,
+    pub after: WordStats,
+}
+
+/// Result of `diff`. Paths are aligned from the root: nodes at the same path are compared,
+/// and below a changed question only moved subtrees are reported.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeDiff {
+    pub changed: Vec<ChangedQuestion>,
+    pub moved: Vec<MovedSubtree>,
+    /// Words in both trees, in the second tree's Yes-first order
+    pub words: Vec<WordChange>,
+    /// Words only in the first tree
+    pub removed: Vec<String>,
+    /// Words only in the second tree
+    pub added: Vec<String>,
+}
+
+impl TreeDiff {
+    /// Whether the trees ask the same questions of the same words
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+            && self.moved.is_empty()
+            && self.words.is_empty()
+            && self.removed.is_empty()
+            && self.added.is_empty()
+    }
+}
+
+/// Compare two trees, `before` and `after`, aligning them by path. Word statistics count the
+/// No edge of each Repeat as `repeat_cost` says.
+pub fn diff(before: &Node, after: &Node, repeat_cost: RepeatCost) -> TreeDiff {
+    let mut places = HashMap::new();
+    index_subtrees(before, &mut Vec::new(), &mut places);
+    let mut out = TreeDiff::default();
+    visit(after, Some(before), &mut Vec::new(), &places, &mut out);
+
+    let before_paths = word_paths(before, repeat_cost);
+    let after_paths = word_paths(after, repeat_cost);
+    for path in &after_paths {
+        match before_paths.iter().find(|old| old.word == path.word) {
+            Some(old) if WordStats::from(old) != WordStats::from(path) => out.words.push(WordChange {
+                word: path.word.clone(),
+                before: WordStats::from(old),
+                after: WordStats::from(path),
+            }),
+            Some(_) => {}
+            None => out.added.push(path.word.clone()),
+        }
+    }
+    out.removed = before_paths
+        .into_iter()
+        .filter(|old| !after_paths.iter().any(|path| path.word == old.word))
+        .map(|old| old.word)
+        .collect();
+    out
+}
+
+/// Path of every subtree of `node` other than leaves, the first one for repeated subtrees
+fn index_subtrees<'a>(node: &'a Node, path: &mut Vec<Branch>, places: &mut HashMap<&'a Node, Vec<Branch>>) {
+    if matches!(node, Node::Leaf(_)) {
+        return;
+    }
+    places.entry(node).or_insert_with(|| path.clone());
+    for branch in [Branch::Yes, Branch::No] {
+        if let Some(child) = child(node, branch) {
+            path.push(branch);
+            index_subtrees(child, path, places);
+            path.pop();
+        }
+    }
+}
+
+fn visit(
+    after: &Node,
+    before: Option<&Node>,
+    path: &mut Vec<Branch>,
+    places: &HashMap<&Node, Vec<Branch>>,
+    out: &mut TreeDiff,
+) {
+    if before == Some(after) {
+        return;
+    }
+    if let Some(from) = places.get(after) {
+        let words = word_paths(after, RepeatCost::Free).into_iter().map(|path| path.word).collect();
+        out.moved.push(MovedSubtree { from: from.clone(), to: path.clone(), words });
+        return;
+    }
+
+    let info = NodeInfo::from_node(after);
+    let aligned = match before {
+        Some(before) if NodeInfo::from_node(before) == info => Some(before),
+        Some(before) => {
+            out.changed.push(ChangedQuestion { path: path.clone(), before: NodeInfo::from_node(before), after: info });
+            None
+        }
+        None => None,
+    };
+    for branch in [Branch::Yes, Branch::No] {
+        if let Some(next) = child(after, branch) {
+            path.push(branch);
+            visit(next, aligned.and_then(|before| child(before, branch)), path, places, out);
+            path.pop();
+        }
+    }
+}
+
+/// Render `after` as an ASCII tree, each line annotated with how it differs from the tree
+/// `diff` compared it with, followed by the removed words.
+pub fn format_diff(diff: &TreeDiff, after: &Node) -> String {
+    if diff.is_empty() {
+        return "The trees are the same\n".to_string();
+    }
+    let notes = Notes {
+        changed: diff.changed.iter().map(|change| (change.path.as_slice(), &change.before)).collect(),
+        moved: diff.moved.iter().map(|moved| (moved.to.as_slice(), moved.from.as_slice())).collect(),
+        words: diff.words.iter().map(|change| (change.word.as_str(), change)).collect(),
+        added: diff.added.iter().map(String::as_str).collect(),
+    };
+    let mut out = String::new();
+    notes.render(after, "", "", "", &mut Vec::new(), &mut out);
+    if !diff.removed.is_empty() {
+        let removed: Vec<String> = diff.removed.iter().map(|word| capitalize_first(word)).collect();
+        out.push_str(&format!("Removed: {}\n", removed.join(", ")));
+    }
+    out
+}
+
+/// Annotations of `format_diff`, looked up while rendering
+struct Notes<'a> {
+    changed: HashMap<&'a [Branch], &'a NodeInfo>,
+    moved: HashMap<&'a [Branch], &'a [Branch]>,
+    words: HashMap<&'a str, &'a WordChange>,
+    added: HashSet<&'a str>,
+}
+
+impl Notes<'_> {
+    fn annotate(&self, node: &Node, path: &[Branch]) -> Vec<String> {
+        let mut notes = Vec::new();
+        if let Some(before) = self.changed.get(path) {
+            notes.push(format!("was: {}", format_question(before)));
+        }
+        if let Some(from) = self.moved.get(path) {
+            notes.push(format!("moved from {}", format_path(from)));
+        }
+        if let Node::Leaf(word) = node {
+            if self.added.contains(word.as_str()) {
+                notes.push("new word".to_string());
+            }
+            if let Some(change) = self.words.get(word.as_str()) {
+                let (before, after) = (change.before, change.after);
+                for (name, old, new) in [
+                    ("questions", before.questions, after.questions),
+                    ("No", before.nos, after.nos),
+                    ("hard No", before.hard_nos, after.hard_nos),
+                ] {
+                    if old != new {
+                        notes.push(format!("{name} {old} → {new}"));
+                    }
+                }
+            }
+        }
+        notes
+    }
+
+    fn render(&self, node: &Node, label: &str, first: &str, rest: &str, path: &mut Vec<Branch>, out: &mut String) {
+        let mut line = format!("{first}{label}{}", format_question(&NodeInfo::from_node(node)));
+        if matches!(node, Node::YesSplit { .. }) {
+            line.push_str(" (yes only)");
+        }
+        let notes = self.annotate(node, path);
+        if !notes.is_empty() {
+            line.push_str(&format!("  [{}]", notes.join("; ")));
+        }
+        out.push_str(&line);
+        out.push('\n');
+
+        // A Repeat's Yes answer is its word
+        let repeated;
+        let mut children: Vec<(Branch, &Node)> = Vec::new();
+        if let Node::Repeat { word, .. } = node {
+            repeated = Node::Leaf(word.clone());
+            children.push((Branch::Yes, &repeated));
+        }
+        children.extend([Branch::Yes, Branch::No].into_iter().filter_map(|b| child(node, b).map(|c| (b, c))));
+        let count = children.len();
+        for (idx, (branch, next)) in children.into_iter().enumerate() {
+            let last = idx + 1 == count;
+            let (first, more) = if last { ("└─ ", "   ") } else { ("├─ ", "│  ") };
+            let label = match branch {
+                Branch::Yes => "Yes: ",
+                Branch::No => "No: ",
+            };
+            path.push(branch);
+            self.render(next, label, &format!("{rest}{first}"), &format!("{rest}{more}"), path, out);
+            path.pop();
+        }
+    }
+}
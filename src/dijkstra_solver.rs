@@ -3,24 +3,101 @@ use std::rc::Rc;
 use hashbrown::HashMap;
 use smallvec::SmallVec;
 
-use crate::cost::{add_no_edge, add_yes_split, compare_costs, estimate_cost, Cost};
+use crate::config::SolverConfig;
+use crate::cost::{add_no_edge, add_questions, add_repeat_edge, add_yes_split, admissible_split_bound,
+                  admissible_split_estimate, estimate_cost, min_questions, Cost};
 use crate::node::{Node, NodeRef, Solution, Position, combine_positional_split, combine_yes_split};
 use crate::constraints::{Constraints, get_reciprocal, split_allowed, branch_constraints};
 use crate::context::{Context, Mask, mask_count, single_word_from_mask, partitions, letters_present};
 
 /// Memoization key for solve().
 ///
-/// Note: prioritize_soft_no is NOT included because it's constant throughout a single
+/// Note: the `SolverConfig` is NOT included because it's constant throughout a single
 /// solve() call tree (memo is created fresh in minimal_trees and passed down).
+/// The `Budget` fields are only `Some` when the matching limit is set, so unlimited
+/// solves share entries regardless of depth or runs of Nos. `repeats_left` counts the
+/// Repeats still allowed on the path, so subtrees below different numbers of Repeats
+/// never share entries.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) struct Key
 {
     mask: Mask,
     forbidden: u32,
     allowed_primary_once: u32,
-    allow_repeat: bool,
+    repeats_left: u32,
     parent_position: Option<Position>,
-    parent_letter: Option<usize>
+    parent_letter: Option<usize>,
+    budget: Budget
+}
+
+#[cfg(test)]
+impl Key
+{
+    pub(crate) const fn mask(&self) -> Mask
+    {
+        self.mask
+    }
+
+    pub(crate) const fn repeats_left(&self) -> u32
+    {
+        self.repeats_left
+    }
+}
+
+/// What is left of the per-path hard limits of the `SolverConfig` when reaching a subtree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Budget
+{
+    /// Questions left before `max_depth` is reached.
+    depth: Option<u32>,
+    /// No edges left before the current run exceeds `max_consecutive_nos`.
+    streak: Option<u32>
+}
+
+impl Budget
+{
+    pub(crate) const fn new(max_depth: Option<u32>, max_consecutive_nos: Option<u32>) -> Self
+    {
+        Budget { depth: max_depth,
+                 streak: max_consecutive_nos }
+    }
+
+    /// Budget of the Yes branch of a split: one question spent, and the run of Nos ends.
+    fn yes_branch(self, config: &SolverConfig) -> Self
+    {
+        Budget { depth: spend(self.depth, 1),
+                 streak: config.max_consecutive_nos }
+    }
+
+    /// Budget of the No branch of a split, below `yes_splits` `YesSplit`s.
+    /// Any `YesSplit` ends the run started by the No edge.
+    fn no_branch(self, config: &SolverConfig, yes_splits: u32) -> Self
+    {
+        let streak = if yes_splits == 0 { spend(self.streak, 1) } else { config.max_consecutive_nos };
+        Budget { depth: spend(self.depth, 1 + yes_splits),
+                 streak }
+    }
+
+    /// Budget of the No branch of a Repeat: nobody answers a free one, so the run of Nos
+    /// carries over; one that costs a No edge extends the run instead.
+    fn repeat_branch(self, config: &SolverConfig) -> Self
+    {
+        let streak = if config.repeat_cost.no_edge().is_some() { spend(self.streak, 1) } else { self.streak };
+        Budget { depth: spend(self.depth, 1),
+                 streak }
+    }
+
+    /// Whether the current run of Nos can take one more No edge.
+    fn allows_no_edge(self) -> bool
+    {
+        self.streak != Some(0)
+    }
+}
+
+/// Saturates at 0: a child with two or more words is then pruned as unsolvable.
+fn spend(limit: Option<u32>, amount: u32) -> Option<u32>
+{
+    limit.map(|left| left.saturating_sub(amount))
 }
 
 const fn get_position_masks<'a>(ctx: &'a Context<'a>, position: Position) -> &'a [Mask; 26]
@@ -119,9 +196,15 @@ fn generate_position_splits(position: Position,
 {
     let mut splits = Vec::new();
     let position_masks = get_position_masks(ctx, position);
+    let mixed_masks = &ctx.mixed_masks[position as usize];
 
     for (idx, yes, no) in partitions(mask, position_masks, &ctx.global_letters)
     {
+        // A word whose spellings answer differently could end up on either side
+        if mask & mixed_masks[idx] != 0
+        {
+            continue;
+        }
         let test_letter = (b'a' + idx as u8) as char;
 
         // 1. Soft split with reciprocal at same position
@@ -167,9 +250,9 @@ fn generate_position_splits(position: Position,
             // We only check the No branch because that's where the requirement applies
             let positions_collide_for_no_branch = {
                 let mut collides = false;
-                for (word_idx, word) in ctx.words.iter().enumerate()
+                'words: for word_idx in (0..ctx.words.len()).filter(|word_idx| no & (1 << word_idx) != 0)
                 {
-                    if no & (1 << word_idx) != 0
+                    for word in ctx.spellings(word_idx)
                     {
                         let word_len = word.chars().count();
                         if let (Some(idx1), Some(idx2)) =
@@ -178,7 +261,7 @@ fn generate_position_splits(position: Position,
                             if idx1 == idx2
                             {
                                 collides = true;
-                                break;
+                                break 'words;
                             }
                         }
                     }
@@ -268,29 +351,32 @@ fn generate_position_splits(position: Position,
     splits
 }
 
-const fn make_key(mask: Mask, constraints: &Constraints, allow_repeat: bool) -> Key
+const fn make_key(mask: Mask, constraints: &Constraints, repeats_left: u32, budget: Budget) -> Key
 {
     Key { mask,
           forbidden: constraints.forbidden_primary | constraints.forbidden_secondary,
           allowed_primary_once: constraints.allowed_primary_once,
-          allow_repeat,
+          repeats_left,
           parent_position: constraints.parent_position,
-          parent_letter: constraints.parent_letter }
+          parent_letter: constraints.parent_letter,
+          budget }
 }
 
 pub(crate) fn solve(mask: Mask,
                     ctx: &Context<'_>,
-                    allow_repeat: bool,
-                    prioritize_soft_no: bool,
-                    redeeming_yes: u32,
+                    repeats_left: u32,
+                    config: &SolverConfig,
                     constraints: Constraints,
+                    budget: Budget,
                     memo: &mut HashMap<Key, Solution>)
                     -> Solution
 {
+    let redeeming_yes = config.redeeming_yes;
+    let free_repeats = config.free_repeats(repeats_left);
     let present_letters = letters_present(mask, ctx);
     let constraints = constraints.prune(present_letters);
 
-    let key = make_key(mask, &constraints, allow_repeat);
+    let key = make_key(mask, &constraints, repeats_left, budget);
     if let Some(hit) = memo.get(&key)
     {
         return hit.clone();
@@ -298,6 +384,20 @@ pub(crate) fn solve(mask: Mask,
 
     let count = mask_count(mask);
 
+    // Out of steps: unwind without memoizing, the caller resumes later with the same memo
+    if !ctx.take_step()
+    {
+        return Solution::unsolvable(count);
+    }
+
+    // Depth cap: even a perfectly balanced tree would need more questions than are left
+    if budget.depth.is_some_and(|depth| min_questions(count).0 > depth)
+    {
+        let sol = Solution::unsolvable(count);
+        memo.insert(key, sol.clone());
+        return sol;
+    }
+
     // Leaf node
     if count == 1
     {
@@ -310,6 +410,10 @@ pub(crate) fn solve(mask: Mask,
                                           redeemed_sum_hard_nos: 0,
                                           sum_nos: 0,
                                           redeemed_sum_nos: 0,
+                                          max_consecutive_nos: 0,
+                                          leading_nos: 0,
+                                          max_questions: 0,
+                                          sum_questions: 0,
                                           word_count: 1 },
                              trees: vec![Rc::new(Node::Leaf(word))] };
         memo.insert(key, sol.clone());
@@ -319,24 +423,42 @@ pub(crate) fn solve(mask: Mask,
     // Collect all possible split candidates with their costs
     let mut candidates: Vec<(Cost, SplitSpec)> = Vec::new();
 
-    // Generate all possible splits across all position types
-    for position in &[Position::Contains,
-                      Position::First,
-                      Position::Second,
-                      Position::Third,
-                      Position::ThirdToLast,
-                      Position::SecondToLast,
-                      Position::Last,
-                      Position::Double,
-                      Position::Triple]
+    // Generate all possible splits across all position types,
+    // unless the current run of Nos cannot afford the No edge of a split
+    let split_positions: &[Position] = if budget.allows_no_edge()
+    {
+        &[Position::Contains,
+          Position::First,
+          Position::Second,
+          Position::Third,
+          Position::ThirdToLast,
+          Position::SecondToLast,
+          Position::Last,
+          Position::Double,
+          Position::Triple]
+    }
+    else
+    {
+        &[]
+    };
+    for position in split_positions
     {
         let splits = generate_position_splits(*position, mask, ctx, &constraints);
 
         for spec in splits
         {
+            // The weighted mode prunes on a scalar, so it needs an admissible estimate
+            if config.weights.is_some()
+            {
+                let est_cost =
+                    admissible_split_estimate(spec.yes, spec.no, spec.is_hard, free_repeats, redeeming_yes);
+                candidates.push((est_cost, spec));
+                continue;
+            }
+
             // Estimate the cost of this split
-            let est_yes = estimate_cost(spec.yes, allow_repeat, redeeming_yes);
-            let est_no = estimate_cost(spec.no, allow_repeat, redeeming_yes);
+            let est_yes = estimate_cost(spec.yes, free_repeats, redeeming_yes);
+            let est_no = estimate_cost(spec.no, free_repeats, redeeming_yes);
 
             let hard_nos = if spec.is_hard
             {
@@ -379,6 +501,11 @@ pub(crate) fn solve(mask: Mask,
                                    + est_no.redeemed_sum_nos
                                    + (est_no.word_count as i32 * redeeming_yes as i32);
 
+            // The No edge of the split starts a run of at least one No
+            let max_consecutive_nos = est_yes.max_consecutive_nos.max(est_no.max_consecutive_nos).max(1);
+            let max_questions = 1 + est_yes.max_questions.max(est_no.max_questions);
+            let sum_questions = est_yes.sum_questions + est_no.sum_questions + est_yes.word_count + est_no.word_count;
+
             let est_cost = Cost { hard_nos,
                                   redeemed_hard_nos,
                                   nos,
@@ -387,6 +514,10 @@ pub(crate) fn solve(mask: Mask,
                                   redeemed_sum_hard_nos,
                                   sum_nos,
                                   redeemed_sum_nos,
+                                  max_consecutive_nos,
+                                  leading_nos: 1,
+                                  max_questions,
+                                  sum_questions,
                                   word_count: est_yes.word_count + est_no.word_count };
 
             candidates.push((est_cost, spec));
@@ -394,13 +525,14 @@ pub(crate) fn solve(mask: Mask,
     }
 
     // Sort candidates by estimated cost (best first)
-    candidates.sort_by(|a, b| compare_costs(&a.0, &b.0, prioritize_soft_no));
+    candidates.sort_by(|a, b| config.compare(&a.0, &b.0));
 
     let mut best_cost: Option<Cost> = None;
     let mut best_trees: SmallVec<[NodeRef; 5]> = SmallVec::new();
 
-    // Try Repeat nodes first (if allowed)
-    if allow_repeat && count >= 2
+    // Try Repeat nodes first (if any are left, and their No edge fits in the run of Nos)
+    let repeat_no_edge = config.repeat_cost.no_edge();
+    if repeats_left > 0 && count >= 2 && (repeat_no_edge.is_none() || budget.allows_no_edge())
     {
         for (idx, word) in ctx.words.iter().enumerate().filter(|(idx, _)| mask & ((1 as Mask) << idx) != 0)
         {
@@ -411,13 +543,14 @@ pub(crate) fn solve(mask: Mask,
             repeat_constraints.parent_position = None;
             repeat_constraints.parent_letter = None;
             let no_sol =
-                solve(no_mask, ctx, false, prioritize_soft_no, redeeming_yes, repeat_constraints, memo);
+                solve(no_mask, ctx, repeats_left - 1, config, repeat_constraints, budget.repeat_branch(config), memo);
 
             if no_sol.is_unsolvable()
             {
                 continue;
             }
 
+            // The Repeat itself is a question for every word, including the repeated one
             let yes_cost = Cost { hard_nos: 0,
                                   redeemed_hard_nos: 0,
                                   nos: 0,
@@ -426,18 +559,27 @@ pub(crate) fn solve(mask: Mask,
                                   redeemed_sum_hard_nos: 0,
                                   sum_nos: 0,
                                   redeemed_sum_nos: 0,
+                                  max_consecutive_nos: 0,
+                                  leading_nos: 0,
+                                  max_questions: 1,
+                                  sum_questions: 1,
                                   word_count: 1 };
+            let no_questions = add_questions(&no_sol.cost, 1);
+            let no_cost = add_repeat_edge(&no_sol.cost, repeat_no_edge, redeeming_yes as i32);
 
             let branch_cost =
-                Cost { hard_nos: no_sol.cost.hard_nos.max(yes_cost.hard_nos),
-                       redeemed_hard_nos: no_sol.cost.redeemed_hard_nos.max(yes_cost.redeemed_hard_nos),
-                       nos: no_sol.cost.nos.max(yes_cost.nos),
-                       redeemed_nos: no_sol.cost.redeemed_nos.max(yes_cost.redeemed_nos),
-                       sum_hard_nos: yes_cost.sum_hard_nos + no_sol.cost.sum_hard_nos,
-                       redeemed_sum_hard_nos: yes_cost.redeemed_sum_hard_nos
-                                              + no_sol.cost.redeemed_sum_hard_nos,
-                       sum_nos: yes_cost.sum_nos + no_sol.cost.sum_nos,
-                       redeemed_sum_nos: yes_cost.redeemed_sum_nos + no_sol.cost.redeemed_sum_nos,
+                Cost { hard_nos: no_cost.hard_nos.max(yes_cost.hard_nos),
+                       redeemed_hard_nos: no_cost.redeemed_hard_nos.max(yes_cost.redeemed_hard_nos),
+                       nos: no_cost.nos.max(yes_cost.nos),
+                       redeemed_nos: no_cost.redeemed_nos.max(yes_cost.redeemed_nos),
+                       sum_hard_nos: yes_cost.sum_hard_nos + no_cost.sum_hard_nos,
+                       redeemed_sum_hard_nos: yes_cost.redeemed_sum_hard_nos + no_cost.redeemed_sum_hard_nos,
+                       sum_nos: yes_cost.sum_nos + no_cost.sum_nos,
+                       redeemed_sum_nos: yes_cost.redeemed_sum_nos + no_cost.redeemed_sum_nos,
+                       max_consecutive_nos: no_cost.max_consecutive_nos,
+                       leading_nos: no_cost.leading_nos,
+                       max_questions: no_questions.max_questions.max(yes_cost.max_questions),
+                       sum_questions: yes_cost.sum_questions + no_questions.sum_questions,
                        word_count: yes_cost.word_count + no_sol.cost.word_count };
 
             match best_cost
@@ -450,7 +592,7 @@ pub(crate) fn solve(mask: Mask,
                         best_trees.push(Rc::new(Node::Repeat { word: word.clone(), no: Rc::clone(n) }));
                     }
                 }
-                Some(ref current) => match compare_costs(&branch_cost, current, prioritize_soft_no)
+                Some(ref current) => match config.compare(&branch_cost, current)
                 {
                     Ordering::Less =>
                     {
@@ -481,7 +623,7 @@ pub(crate) fn solve(mask: Mask,
         // Pruning: if we already have a solution and this candidate's estimate is worse, skip
         if let Some(ref current_best) = best_cost
         {
-            if compare_costs(&est_cost, current_best, prioritize_soft_no) == Ordering::Greater
+            if config.compare(&est_cost, current_best) == Ordering::Greater
             {
                 continue;
             }
@@ -508,31 +650,50 @@ pub(crate) fn solve(mask: Mask,
 
         // Solve children recursively
         let no_sol =
-            solve(spec.no, ctx, allow_repeat, prioritize_soft_no, redeeming_yes, no_constraints, memo);
+            solve(spec.no, ctx, repeats_left, config, no_constraints, budget.no_branch(config, 0), memo);
 
-        if no_sol.is_unsolvable()
+        // YesSplits need enough words in the No branch
+        let no_word_count = mask_count(spec.no);
+        let min_words_for_yes_split = free_repeats + 2;
+
+        // A No branch whose run of Nos is too long may still fit once a YesSplit ends the run
+        let yes_splits_can_rescue =
+            config.max_consecutive_nos.is_some() && redeeming_yes > 0 && no_word_count >= min_words_for_yes_split;
+        if no_sol.is_unsolvable() && !yes_splits_can_rescue
         {
             continue;
         }
 
         // Pruning: check if no branch cost already exceeds best
         // Account for potential YesSplits that could reduce cost
-        if let Some(ref current_best) = best_cost
+        // (when they can rescue it, they also relax its run of Nos: see the bound below)
+        if let Some(current_best) = best_cost.as_ref().filter(|_| ctx.prunes_no_branches() && !yes_splits_can_rescue)
         {
-            let mut no_cost = add_no_edge(&no_sol.cost, spec.is_hard, redeeming_yes as i32);
+            let mut no_cost = no_sol.cost;
 
             // Optimistically assume we can add up to redeeming_yes YesSplits
-            let no_word_count = mask_count(spec.no);
-            let min_words_for_yes_split = if allow_repeat { 3 } else { 2 };
-
             let max_yes_splits =
                 if redeeming_yes > 0 && no_word_count >= min_words_for_yes_split { redeeming_yes } else { 0 };
 
-            // Apply maximum possible YesSplits (reduces redeemed costs)
+            // Apply maximum possible YesSplits (reduces redeemed costs), below the No edge
             for _ in 0..max_yes_splits
             {
                 no_cost = add_yes_split(&no_cost);
             }
+            no_cost = add_no_edge(&no_cost, spec.is_hard, redeeming_yes as i32);
+
+            // The weighted mode needs a true lower bound: add the exact No-branch sums and an
+            // admissible estimate of the Yes branch
+            if config.weights.is_some()
+            {
+                no_cost.sum_nos += no_sol.cost.word_count;
+                no_cost.redeemed_sum_nos += no_sol.cost.word_count as i32 * redeeming_yes as i32;
+                if spec.is_hard
+                {
+                    no_cost.sum_hard_nos += no_sol.cost.word_count;
+                    no_cost.redeemed_sum_hard_nos += no_sol.cost.word_count as i32 * redeeming_yes as i32;
+                }
+            }
 
             // Cap redeemed costs to not be negative
             no_cost.redeemed_hard_nos = no_cost.redeemed_hard_nos.max(0);
@@ -540,31 +701,48 @@ pub(crate) fn solve(mask: Mask,
             no_cost.redeemed_sum_hard_nos = no_cost.redeemed_sum_hard_nos.max(0);
             no_cost.redeemed_sum_nos = no_cost.redeemed_sum_nos.max(0);
 
+            if config.weights.is_some()
+            {
+                no_cost = config.with_yes_estimate(no_cost, spec.yes, free_repeats);
+            }
+
             // Use compare_costs to check if even with max YesSplits, this no branch is worse than best
-            if compare_costs(&no_cost, current_best, prioritize_soft_no) == Ordering::Greater
+            if config.compare(&no_cost, current_best) == Ordering::Greater
             {
                 continue;
             }
         }
 
         let yes_sol =
-            solve(spec.yes, ctx, allow_repeat, prioritize_soft_no, redeeming_yes, yes_constraints, memo);
+            solve(spec.yes, ctx, repeats_left, config, yes_constraints, budget.yes_branch(config), memo);
 
         if yes_sol.is_unsolvable()
         {
             continue;
         }
 
+        // Pruning when YesSplits can rescue the No branch: its solution under the run of Nos
+        // does not bound theirs, so bound the split by the solved Yes branch and an estimate of
+        // the No branch from its words alone, fully redeemed by YesSplits
+        if let Some(current_best) = best_cost.as_ref().filter(|_| ctx.prunes_no_branches() && yes_splits_can_rescue)
+        {
+            let bound = admissible_split_bound(&yes_sol.cost, spec.no, spec.is_hard, free_repeats, redeeming_yes);
+            if config.compare(&bound, current_best) == Ordering::Greater
+            {
+                continue;
+            }
+        }
+
         // Helper to process a split (base or augmented with YesSplits)
         let mut process_split = |no_branch_node: &NodeRef, yes_split_count: u32| {
-            // Calculate cost with YesSplit adjustments
-            let mut no_cost = add_no_edge(&no_sol.cost, spec.is_hard, redeeming_yes as i32);
-
-            // Apply YesSplit cost adjustments (-1 per YesSplit)
+            // Calculate cost with YesSplit adjustments (-1 per YesSplit),
+            // the YesSplits sitting between the No edge and the subtree
+            let mut no_cost = no_sol.cost;
             for _ in 0..yes_split_count
             {
                 no_cost = add_yes_split(&no_cost);
             }
+            no_cost = add_no_edge(&no_cost, spec.is_hard, redeeming_yes as i32);
 
             // Add word_count contributions to no_cost sum metrics
             no_cost.sum_nos += no_sol.cost.word_count;
@@ -586,12 +764,18 @@ pub(crate) fn solve(mask: Mask,
             let redeemed_hard_nos = yes_cost.redeemed_hard_nos.max(no_cost.redeemed_hard_nos);
             let nos = yes_cost.nos.max(no_cost.nos);
             let redeemed_nos = yes_cost.redeemed_nos.max(no_cost.redeemed_nos);
+            let max_consecutive_nos = yes_cost.max_consecutive_nos.max(no_cost.max_consecutive_nos);
+            let leading_nos = yes_cost.leading_nos.max(no_cost.leading_nos);
             let total_sum_nos = yes_sol.cost.sum_nos + no_cost.sum_nos;
             let total_sum_hard_nos = yes_sol.cost.sum_hard_nos + no_cost.sum_hard_nos;
             let total_redeemed_sum_nos = yes_sol.cost.redeemed_sum_nos + no_cost.redeemed_sum_nos;
             let total_redeemed_sum_hard_nos =
                 yes_sol.cost.redeemed_sum_hard_nos + no_cost.redeemed_sum_hard_nos;
 
+            // The split question is asked on both branches, the YesSplits only on the No branch
+            let yes_questions = add_questions(&yes_sol.cost, 1);
+            let no_questions = add_questions(&no_sol.cost, 1 + yes_split_count);
+
             let branch_cost = Cost { hard_nos,
                                      redeemed_hard_nos,
                                      nos,
@@ -600,6 +784,10 @@ pub(crate) fn solve(mask: Mask,
                                      redeemed_sum_hard_nos: total_redeemed_sum_hard_nos,
                                      sum_nos: total_sum_nos,
                                      redeemed_sum_nos: total_redeemed_sum_nos,
+                                     max_consecutive_nos,
+                                     leading_nos,
+                                     max_questions: yes_questions.max_questions.max(no_questions.max_questions),
+                                     sum_questions: yes_questions.sum_questions + no_questions.sum_questions,
                                      word_count: yes_sol.cost.word_count + no_sol.cost.word_count };
 
             // Update best if this is better
@@ -618,7 +806,7 @@ pub(crate) fn solve(mask: Mask,
                                                                  no_branch_node));
                     }
                 }
-                Some(ref current) => match compare_costs(&branch_cost, current, prioritize_soft_no)
+                Some(ref current) => match config.compare(&branch_cost, current)
                 {
                     Ordering::Less =>
                     {
@@ -660,9 +848,6 @@ pub(crate) fn solve(mask: Mask,
 
         // Build YesSplit layers incrementally with re-solving
         // Only add YesSplits if no branch has enough words and redeeming_yes > 0
-        let no_word_count = mask_count(spec.no);
-        let min_words_for_yes_split = if allow_repeat { 3 } else { 2 };
-
         if redeeming_yes > 0 && no_word_count >= min_words_for_yes_split
         {
             // Track (position, letter_idx) pairs already used in parent split
@@ -714,10 +899,10 @@ pub(crate) fn solve(mask: Mask,
                         // RE-SOLVE with updated constraints
                         let new_sol = solve(spec.no,
                                             ctx,
-                                            allow_repeat,
-                                            prioritize_soft_no,
-                                            redeeming_yes,
+                                            repeats_left,
+                                            config,
                                             new_constraints,
+                                            budget.no_branch(config, prev_chain.len() as u32 + 1),
                                             memo);
 
                         // Check if this YesSplit produced a bad/unsolvable result
@@ -732,13 +917,13 @@ pub(crate) fn solve(mask: Mask,
                         let yes_split_count = new_chain.len() as u32;
 
                         // Calculate cost for this YesSplit combination
-                        let mut no_cost = add_no_edge(&new_sol.cost, spec.is_hard, redeeming_yes as i32);
-
-                        // Apply YesSplit cost adjustments (-1 per YesSplit)
+                        // Apply YesSplit cost adjustments (-1 per YesSplit), below the No edge
+                        let mut no_cost = new_sol.cost;
                         for _ in 0..yes_split_count
                         {
                             no_cost = add_yes_split(&no_cost);
                         }
+                        no_cost = add_no_edge(&no_cost, spec.is_hard, redeeming_yes as i32);
 
                         // Add word_count contributions to no_cost sum metrics
                         no_cost.sum_nos += new_sol.cost.word_count;
@@ -761,12 +946,17 @@ pub(crate) fn solve(mask: Mask,
                         let redeemed_hard_nos = yes_cost.redeemed_hard_nos.max(no_cost.redeemed_hard_nos);
                         let nos = yes_cost.nos.max(no_cost.nos);
                         let redeemed_nos = yes_cost.redeemed_nos.max(no_cost.redeemed_nos);
+                        let max_consecutive_nos = yes_cost.max_consecutive_nos.max(no_cost.max_consecutive_nos);
+                        let leading_nos = yes_cost.leading_nos.max(no_cost.leading_nos);
                         let total_sum_nos = yes_sol.cost.sum_nos + no_cost.sum_nos;
                         let total_sum_hard_nos = yes_sol.cost.sum_hard_nos + no_cost.sum_hard_nos;
                         let total_redeemed_sum_nos = yes_sol.cost.redeemed_sum_nos + no_cost.redeemed_sum_nos;
                         let total_redeemed_sum_hard_nos =
                             yes_sol.cost.redeemed_sum_hard_nos + no_cost.redeemed_sum_hard_nos;
 
+                        let yes_questions = add_questions(&yes_sol.cost, 1);
+                        let no_questions = add_questions(&new_sol.cost, 1 + yes_split_count);
+
                         let branch_cost = Cost { hard_nos,
                                                  redeemed_hard_nos,
                                                  nos,
@@ -775,13 +965,19 @@ pub(crate) fn solve(mask: Mask,
                                                  redeemed_sum_hard_nos: total_redeemed_sum_hard_nos,
                                                  sum_nos: total_sum_nos,
                                                  redeemed_sum_nos: total_redeemed_sum_nos,
+                                                 max_consecutive_nos,
+                                                 leading_nos,
+                                                 max_questions: yes_questions.max_questions
+                                                                .max(no_questions.max_questions),
+                                                 sum_questions: yes_questions.sum_questions
+                                                                + no_questions.sum_questions,
                                                  word_count: yes_sol.cost.word_count
                                                              + new_sol.cost.word_count };
 
                         // Check if this solution is competitive with current best
                         let should_skip = if let Some(ref current_best) = best_cost
                         {
-                            compare_costs(&branch_cost, current_best, prioritize_soft_no) == Ordering::Greater
+                            config.compare(&branch_cost, current_best) == Ordering::Greater
                         }
                         else
                         {
@@ -829,7 +1025,7 @@ pub(crate) fn solve(mask: Mask,
                                 }
                                 Some(ref current) =>
                                 {
-                                    match compare_costs(&branch_cost, current, prioritize_soft_no)
+                                    match config.compare(&branch_cost, current)
                                     {
                                         Ordering::Less =>
                                         {
@@ -884,6 +1080,10 @@ pub(crate) fn solve(mask: Mask,
     {
         Solution::unsolvable(mask_count(mask))
     };
-    memo.insert(key, sol.clone());
+    // Children cut short by the step limit may have hidden better trees
+    if !ctx.interrupted()
+    {
+        memo.insert(key, sol.clone());
+    }
     sol
 }
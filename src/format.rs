@@ -1,73 +1,75 @@
+use crate::merged::{Branch, NodeInfo};
 use crate::node::{Node, Position};
-
-pub fn format_tree(node: &Node) -> String {
-    // Helper to capitalize the first letter of a word
-    fn capitalize_first(s: &str) -> String {
-        let mut chars = s.chars();
-        match chars.next() {
-            None => String::new(),
-            Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
-        }
+use crate::report::{Answer, PathStep, WordPath};
+
+/// Capitalize the first letter of a word
+pub(crate) fn capitalize_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
     }
+}
 
-    // Display helper: show question letters in uppercase for clarity in ASCII trees
-    const fn display_letter(c: char) -> char {
-        c.to_ascii_uppercase()
-    }
+/// Display helper: show question letters in uppercase for clarity in ASCII trees
+const fn display_letter(c: char) -> char {
+    c.to_ascii_uppercase()
+}
 
-    // Format a position description
-    fn format_position_question(
-        test_letter: char,
-        test_position: &Position,
-        requirement_letter: char,
-        requirement_position: &Position,
-    ) -> String {
-        let test_letter_upper = display_letter(test_letter);
-        let req_letter_upper = display_letter(requirement_letter);
-
-        // Hard split: test and requirement are the same
-        if test_letter == requirement_letter && test_position == requirement_position {
-            match test_position {
-                Position::Contains => format!("Contains '{test_letter_upper}'?"),
-                Position::First => format!("First letter '{test_letter_upper}'?"),
-                Position::Second => format!("Second letter '{test_letter_upper}'?"),
-                Position::Third => format!("Third letter '{test_letter_upper}'?"),
-                Position::ThirdToLast => format!("Third-to-last letter '{test_letter_upper}'?"),
-                Position::SecondToLast => format!("Second-to-last letter '{test_letter_upper}'?"),
-                Position::Last => format!("Last letter '{test_letter_upper}'?"),
-                Position::Double => format!("Double '{test_letter_upper}'?"),
-                Position::Triple => format!("Triple '{test_letter_upper}'?"),
-            }
-        } else {
-            // Soft split: different test and requirement
-            let test_desc = match test_position {
-                Position::Contains => format!("Contains '{test_letter_upper}'?"),
-                Position::First => format!("First letter '{test_letter_upper}'?"),
-                Position::Second => format!("Second letter '{test_letter_upper}'?"),
-                Position::Third => format!("Third letter '{test_letter_upper}'?"),
-                Position::ThirdToLast => format!("Third-to-last letter '{test_letter_upper}'?"),
-                Position::SecondToLast => format!("Second-to-last letter '{test_letter_upper}'?"),
-                Position::Last => format!("Last letter '{test_letter_upper}'?"),
-                Position::Double => format!("Double '{test_letter_upper}'?"),
-                Position::Triple => format!("Triple '{test_letter_upper}'?"),
-            };
-
-            let req_desc = match requirement_position {
-                Position::Contains => format!("all No contain '{req_letter_upper}'"),
-                Position::First => format!("all No have '{req_letter_upper}' first"),
-                Position::Second => format!("all No have '{req_letter_upper}' second"),
-                Position::Third => format!("all No have '{req_letter_upper}' third"),
-                Position::ThirdToLast => format!("all No have '{req_letter_upper}' third-to-last"),
-                Position::SecondToLast => format!("all No have '{req_letter_upper}' second-to-last"),
-                Position::Last => format!("all No have '{req_letter_upper}' last"),
-                Position::Double => format!("all No double '{req_letter_upper}'"),
-                Position::Triple => format!("all No triple '{req_letter_upper}'"),
-            };
-
-            format!("{test_desc} ({req_desc})")
+/// Format the question asked by a split
+pub(crate) fn format_position_question(
+    test_letter: char,
+    test_position: &Position,
+    requirement_letter: char,
+    requirement_position: &Position,
+) -> String {
+    let test_letter_upper = display_letter(test_letter);
+    let req_letter_upper = display_letter(requirement_letter);
+
+    // Hard split: test and requirement are the same
+    if test_letter == requirement_letter && test_position == requirement_position {
+        match test_position {
+            Position::Contains => format!("Contains '{test_letter_upper}'?"),
+            Position::First => format!("First letter '{test_letter_upper}'?"),
+            Position::Second => format!("Second letter '{test_letter_upper}'?"),
+            Position::Third => format!("Third letter '{test_letter_upper}'?"),
+            Position::ThirdToLast => format!("Third-to-last letter '{test_letter_upper}'?"),
+            Position::SecondToLast => format!("Second-to-last letter '{test_letter_upper}'?"),
+            Position::Last => format!("Last letter '{test_letter_upper}'?"),
+            Position::Double => format!("Double '{test_letter_upper}'?"),
+            Position::Triple => format!("Triple '{test_letter_upper}'?"),
         }
+    } else {
+        // Soft split: different test and requirement
+        let test_desc = match test_position {
+            Position::Contains => format!("Contains '{test_letter_upper}'?"),
+            Position::First => format!("First letter '{test_letter_upper}'?"),
+            Position::Second => format!("Second letter '{test_letter_upper}'?"),
+            Position::Third => format!("Third letter '{test_letter_upper}'?"),
+            Position::ThirdToLast => format!("Third-to-last letter '{test_letter_upper}'?"),
+            Position::SecondToLast => format!("Second-to-last letter '{test_letter_upper}'?"),
+            Position::Last => format!("Last letter '{test_letter_upper}'?"),
+            Position::Double => format!("Double '{test_letter_upper}'?"),
+            Position::Triple => format!("Triple '{test_letter_upper}'?"),
+        };
+
+        let req_desc = match requirement_position {
+            Position::Contains => format!("all No contain '{req_letter_upper}'"),
+            Position::First => format!("all No have '{req_letter_upper}' first"),
+            Position::Second => format!("all No have '{req_letter_upper}' second"),
+            Position::Third => format!("all No have '{req_letter_upper}' third"),
+            Position::ThirdToLast => format!("all No have '{req_letter_upper}' third-to-last"),
+            Position::SecondToLast => format!("all No have '{req_letter_upper}' second-to-last"),
+            Position::Last => format!("all No have '{req_letter_upper}' last"),
+            Position::Double => format!("all No double '{req_letter_upper}'"),
+            Position::Triple => format!("all No triple '{req_letter_upper}'"),
+        };
+
+        format!("{test_desc} ({req_desc})")
     }
+}
 
+pub fn format_tree(node: &Node) -> String {
     // Render a No branch that diverges sideways from the main spine.
     fn render_no_branch(node: &Node, prefix: &str, out: &mut String) {
         match node {
@@ -290,3 +292,82 @@ pub fn format_tree(node: &Node) -> String {
     render_spine(node, "", true, &mut out);
     out
 }
+
+/// Format what a node asks (or, for a leaf, its word)
+pub(crate) fn format_question(info: &NodeInfo) -> String {
+    match info {
+        NodeInfo::Leaf { word } => capitalize_first(word),
+        NodeInfo::Repeat { word } => format!("Repeat {}?", capitalize_first(word)),
+        NodeInfo::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position }
+        | NodeInfo::YesSplit { test_letter, test_position, requirement_letter, requirement_position } => {
+            format_position_question(*test_letter, test_position, *requirement_letter, requirement_position)
+        }
+    }
+}
+
+/// A path for display, e.g. `Yes → No`
+pub(crate) fn format_path(path: &[Branch]) -> String {
+    if path.is_empty() {
+        return "root".to_string();
+    }
+    let steps: Vec<&str> = path
+        .iter()
+        .map(|branch| match branch {
+            Branch::Yes => "Yes",
+            Branch::No => "No",
+        })
+        .collect();
+    steps.join(" → ")
+}
+
+/// Format one step of a word path, e.g. `Contains 'E'? No`
+fn format_step(step: &PathStep) -> String {
+    let question = format_question(&step.question);
+    let answer = match step.answer {
+        Answer::Yes => "Yes",
+        Answer::No => "No",
+    };
+    format!("{question} {answer}")
+}
+
+/// Render per-word path reports as an aligned table, one row per word.
+pub fn format_word_paths(paths: &[WordPath]) -> String {
+    const HEADERS: [&str; 5] = ["Word", "Questions", "No", "Hard No", "Path"];
+
+    let rows: Vec<[String; 5]> = paths
+        .iter()
+        .map(|path| {
+            [
+                capitalize_first(&path.word),
+                path.questions().to_string(),
+                path.nos.to_string(),
+                path.hard_nos.to_string(),
+                path.steps.iter().map(format_step).collect::<Vec<_>>().join(" → "),
+            ]
+        })
+        .collect();
+
+    // The last column is left unpadded
+    let mut widths = HEADERS.map(|header| header.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let header_row = HEADERS.map(String::from);
+    for row in std::iter::once(&header_row).chain(&rows) {
+        let mut line = String::new();
+        for (idx, cell) in row.iter().enumerate() {
+            if idx + 1 < row.len() {
+                line.push_str(&format!("{cell:<width$}  ", width = widths[idx]));
+            } else {
+                line.push_str(cell);
+            }
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
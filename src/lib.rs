@@ -1,5 +1,6 @@
 // Module declarations
 pub mod cost;
+pub mod config;
 pub mod node;
 pub mod constraints;
 pub mod context;
@@ -7,20 +8,55 @@ pub mod dijkstra_solver;
 pub mod format;
 pub mod api;
 pub mod merged;
+pub mod report;
+pub mod script;
+pub mod diagram;
+pub mod svg;
+pub mod notation;
+pub mod validate;
+pub mod wordsets;
+pub mod batch;
+pub mod design;
+pub mod sensitivity;
+pub mod stepper;
+pub mod diff;
+pub mod partition;
+pub mod naturalness;
+pub mod query;
 
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
 
 // Re-export public API
-pub use cost::{Cost, compare_costs};
+pub use cost::{Cost, CostWeights, compare_costs, weighted_cost};
+pub use config::{RepeatCost, SolverConfig};
 pub use node::{Node, NodeRef, Solution};
-pub use format::format_tree;
-pub use api::minimal_trees;
-pub use merged::{MergedNode, MergedOption, NodeInfo};
+pub use format::{format_tree, format_word_paths};
+pub use api::{
+    min_feasible_depth, minimal_trees, minimal_trees_with_aliases, minimal_trees_with_config, try_minimal_trees,
+    try_minimal_trees_with_aliases, DepthInfeasible,
+};
+pub use merged::{Branch, Choice, MergedNode, MergedOption, NodeInfo, Resolution};
+pub use report::{word_paths, Answer, PathStep, WordPath};
+pub use script::{format_script, ScriptTemplates};
+pub use svg::{render_svg, SvgOptions};
+pub use notation::ParseNodeError;
+pub use validate::{tree_cost, tree_cost_with_repeats, validate_tree, TreeIssue};
+pub use wordsets::WordSet;
+pub use batch::{solve_jsonl, BatchRequest, BatchResult, BatchSolver};
+pub use design::{design_subsets, DesignCandidate, DesignError, DesignOptions};
+pub use sensitivity::{sensitivity, SensitivityError, SensitivityReport, WordSensitivity};
+pub use stepper::{Progress, StepSolver};
+pub use query::{Comparison, NodeKind, ParseQueryError, Query, WordMetric};
+pub use naturalness::{naturalness, NaturalnessWeights};
+pub use partition::{format_alternatives, group_by_partition, Alternatives, TreeClass};
+pub use diff::{diff, format_diff, ChangedQuestion, MovedSubtree, TreeDiff, WordChange, WordStats};
 
 // Re-export WASM bindings (they have their own #[wasm_bindgen] attributes)
 #[cfg(target_arch = "wasm32")]
-pub use wasm::{solve_words, zodiac_words};
+pub use wasm::{
+    diff_trees, evaluate_tree, render_tree, solve_words, tree_issues, tree_paths, word_set, word_sets, Solver,
+};
 
 #[cfg(test)]
 mod tests {
@@ -43,6 +79,10 @@ mod tests {
             redeemed_sum_hard_nos: 0,
             sum_nos: 4,
             redeemed_sum_nos: 8,
+            max_consecutive_nos: 2,
+            leading_nos: 2,
+            max_questions: 3,
+            sum_questions: 9,
             word_count: 4,
         };
         let hard_first = Cost {
@@ -54,6 +94,10 @@ mod tests {
             redeemed_sum_hard_nos: 2,
             sum_nos: 2,
             redeemed_sum_nos: 4,
+            max_consecutive_nos: 1,
+            leading_nos: 1,
+            max_questions: 2,
+            sum_questions: 8,
             word_count: 4,
         };
 
@@ -61,6 +105,942 @@ mod tests {
         assert_eq!(compare_costs(&soft_first, &hard_first, false), Ordering::Greater);
     }
 
+    #[test]
+    fn compare_costs_breaks_ties_on_questions() {
+        use std::cmp::Ordering;
+
+        let short = minimal_trees(&words(&["ab", "ac", "b"]), false, true, 2).cost;
+        let long = Cost { max_questions: short.max_questions + 1, ..short };
+        let longer_on_average = Cost { sum_questions: short.sum_questions + 1, ..short };
+
+        for prioritize_soft_no in [true, false] {
+            assert_eq!(compare_costs(&short, &long, prioritize_soft_no), Ordering::Less);
+            assert_eq!(compare_costs(&short, &longer_on_average, prioritize_soft_no), Ordering::Less);
+        }
+    }
+
+    #[test]
+    fn compare_costs_ranks_leading_runs_before_questions() {
+        use cost::add_no_edge;
+        use std::cmp::Ordering;
+
+        // Same longest run of one No, but the quicker subtree starts with it
+        let base = minimal_trees(&words(&["ab", "ac", "b"]), false, true, 2).cost;
+        let quick = Cost { max_consecutive_nos: 1, leading_nos: 1, ..base };
+        let slow = Cost { max_consecutive_nos: 1, leading_nos: 0, max_questions: base.max_questions + 1, ..base };
+
+        for prioritize_soft_no in [true, false] {
+            // Below a No edge, only the slow subtree keeps the longest run at one No
+            let (quick_below, slow_below) = (add_no_edge(&quick, false, 2), add_no_edge(&slow, false, 2));
+            assert_eq!(compare_costs(&slow_below, &quick_below, prioritize_soft_no), Ordering::Less);
+            // so memoizing the subtree must already prefer it
+            assert_eq!(compare_costs(&slow, &quick, prioritize_soft_no), Ordering::Less);
+        }
+    }
+
+    #[test]
+    fn repeat_counts_as_a_question() {
+        // Repeat alpha, then beta is the only word left: one question each
+        let sol = minimal_trees(&words(&["alpha", "beta"]), true, true, 2);
+        assert_eq!((sol.cost.max_questions, sol.cost.sum_questions), (1, 2));
+    }
+
+    #[test]
+    fn weighted_mode_trades_hard_for_soft() {
+        let data = words(&["aries", "taurus", "gemini", "leo"]);
+        let flat = SolverConfig {
+            weights: Some(CostWeights { hard_no: 1.0, soft_no: 1.0, redemption: 0.0, ..CostWeights::default() }),
+            ..SolverConfig::new(false, true, 0)
+        };
+        let hard_averse = SolverConfig {
+            weights: Some(CostWeights { hard_no: 10.0, soft_no: 1.0, redemption: 0.0, ..CostWeights::default() }),
+            ..SolverConfig::new(false, true, 0)
+        };
+
+        // When a hard No costs the same as a soft one, a single hard No beats two soft ones
+        let flat_sol = minimal_trees_with_config(&data, &flat);
+        assert_eq!((flat_sol.cost.hard_nos, flat_sol.cost.nos), (1, 1));
+
+        // A heavy hard No weight flips the trade
+        let averse_sol = minimal_trees_with_config(&data, &hard_averse);
+        assert_eq!((averse_sol.cost.hard_nos, averse_sol.cost.nos), (0, 2));
+    }
+
+    #[test]
+    fn admissible_estimate_bounds_weighted_cost() {
+        use cost::admissible_estimate_cost;
+
+        let weights = CostWeights::default();
+        for (list, max_repeats) in [
+            (&["aries", "taurus", "gemini", "leo", "virgo", "libra"][..], 0),
+            (&["aries", "taurus", "gemini", "leo", "virgo", "libra"][..], 1),
+            (&["aries", "taurus", "gemini", "leo", "virgo", "libra"][..], 2),
+            (&["bar", "car", "bee", "see"][..], 1),
+            (&["bar", "car", "bee", "see"][..], 3),
+            (&["alpha", "beta"][..], 1),
+            (&["aries", "taurus", "gemini", "cancer", "leo", "virgo", "libra", "scorpio"][..], 0),
+            (&["aries", "taurus", "gemini", "cancer", "leo", "virgo", "libra", "scorpio"][..], 1),
+        ] {
+            let data = words(list);
+            let config = SolverConfig {
+                weights: Some(weights),
+                max_repeats,
+                ..SolverConfig::new(max_repeats > 0, false, 2)
+            };
+            let mut memo = hashbrown::HashMap::new();
+            api::try_minimal_trees_with_memo(&data, &config, &mut memo).unwrap();
+            // Every solved subproblem, not just the root, must bound its estimate field by field
+            for (key, sol) in memo.iter().filter(|(_, sol)| !sol.is_unsolvable()) {
+                let estimate = admissible_estimate_cost(key.mask(), key.repeats_left(), 2);
+                let (e, c) = (&estimate, &sol.cost);
+                let bounded = [
+                    (e.hard_nos as i64, c.hard_nos as i64),
+                    (e.redeemed_hard_nos.into(), c.redeemed_hard_nos.into()),
+                    (e.nos.into(), c.nos.into()),
+                    (e.redeemed_nos.into(), c.redeemed_nos.into()),
+                    (e.sum_hard_nos.into(), c.sum_hard_nos.into()),
+                    (e.redeemed_sum_hard_nos.into(), c.redeemed_sum_hard_nos.into()),
+                    (e.sum_nos.into(), c.sum_nos.into()),
+                    (e.redeemed_sum_nos.into(), c.redeemed_sum_nos.into()),
+                    (e.max_consecutive_nos.into(), c.max_consecutive_nos.into()),
+                    (e.max_questions.into(), c.max_questions.into()),
+                    (e.sum_questions.into(), c.sum_questions.into()),
+                ];
+                assert!(
+                    bounded.iter().all(|(estimate, cost)| estimate <= cost)
+                        && weighted_cost(e, &weights) <= weighted_cost(c, &weights),
+                    "estimate {e:?} exceeds solution {c:?} for {list:?}"
+                );
+            }
+        }
+    }
+
+    fn tree_depth(node: &Node) -> u32 {
+        match node {
+            Node::Leaf(_) => 0,
+            Node::Repeat { no, .. } => 1 + tree_depth(no),
+            Node::PositionalSplit { yes, no, .. } => 1 + tree_depth(yes).max(tree_depth(no)),
+            Node::YesSplit { yes, .. } => 1 + tree_depth(yes),
+        }
+    }
+
+    #[test]
+    fn max_depth_caps_every_path() {
+        let data = wordsets::ZODIAC.to_words();
+        let base = SolverConfig::new(false, false, 2);
+        let uncapped = minimal_trees_with_config(&data, &base);
+        assert!(uncapped.cost.max_questions > 4);
+
+        let capped = try_minimal_trees(&data, &SolverConfig { max_depth: Some(4), ..base.clone() }).unwrap();
+        assert_eq!(capped.cost.max_questions, 4);
+        assert!(capped.trees.iter().all(|tree| tree_depth(tree) <= 4));
+
+        // Twelve words cannot be told apart with three binary questions
+        let err = try_minimal_trees(&data, &SolverConfig { max_depth: Some(3), ..base.clone() }).unwrap_err();
+        assert_eq!(err, DepthInfeasible { max_depth: 3, min_feasible_depth: Some(4), max_consecutive_nos: None });
+        assert_eq!(min_feasible_depth(&data, &base), Some(4));
+    }
+
+    /// (longest run of No edges, longest run starting at the root); Repeats carry runs over
+    fn no_runs(node: &Node) -> (u32, u32) {
+        match node {
+            Node::Leaf(_) => (0, 0),
+            Node::Repeat { no, .. } => no_runs(no),
+            Node::PositionalSplit { yes, no, .. } => {
+                let (yes_max, _) = no_runs(yes);
+                let (no_max, no_leading) = no_runs(no);
+                (yes_max.max(no_max).max(no_leading + 1), no_leading + 1)
+            }
+            Node::YesSplit { yes, .. } => (no_runs(yes).0, 0),
+        }
+    }
+
+    #[test]
+    fn max_consecutive_nos_limit_is_enforced() {
+        let data = wordsets::ZODIAC.to_words();
+        let unlimited = minimal_trees(&data, true, true, 2);
+        assert_eq!(unlimited.cost.max_consecutive_nos, 2);
+        assert!(unlimited.trees.iter().all(|tree| no_runs(tree) == (2, unlimited.cost.leading_nos)));
+
+        // Only YesSplits and Repeats can break every run down to a single No
+        let limited = minimal_trees_with_config(
+            &data,
+            &SolverConfig { max_consecutive_nos: Some(1), ..SolverConfig::new(true, true, 2) },
+        );
+        assert_eq!(limited.cost.max_consecutive_nos, 1);
+        assert!(!limited.trees.is_empty());
+        assert!(limited.trees.iter().all(|tree| no_runs(tree).0 <= 1));
+
+        let no_yes_splits = minimal_trees_with_config(
+            &data,
+            &SolverConfig { max_consecutive_nos: Some(1), ..SolverConfig::new(true, true, 0) },
+        );
+        assert!(no_yes_splits.is_unsolvable());
+        // The run limit, not the cap, rules out every depth, and the error says so
+        let capped = SolverConfig { max_depth: Some(8), max_consecutive_nos: Some(1), ..SolverConfig::new(true, true, 0) };
+        let err = try_minimal_trees(&data, &capped).unwrap_err();
+        assert_eq!(err.min_feasible_depth, None);
+        assert!(err.to_string().contains("at most 1 consecutive No"));
+    }
+
+    #[test]
+    fn no_branch_pruning_under_a_no_run_limit_keeps_every_optimal_tree() {
+        // Sorted notation of the optimal trees, which pruning may find in another order
+        fn notations(sol: &Solution) -> Vec<String> {
+            let mut out: Vec<String> = sol.trees.iter().map(|tree| tree.to_string()).collect();
+            out.sort();
+            out
+        }
+
+        for count in [6, 8, 10] {
+            let data = words(&wordsets::ZODIAC.words[..count]);
+            for (allow_repeat, redeeming_yes, max_consecutive_nos, weights) in [
+                (false, 1, 1, None),
+                (true, 1, 1, None),
+                (true, 2, 1, None),
+                (false, 2, 2, None),
+                (true, 2, 2, None),
+                (true, 2, 1, Some(CostWeights::default())),
+            ] {
+                let config = SolverConfig {
+                    max_consecutive_nos: Some(max_consecutive_nos),
+                    weights,
+                    ..SolverConfig::new(allow_repeat, true, redeeming_yes)
+                };
+                let pruned = minimal_trees_with_config(&data, &config);
+                let ctx = context::Context::new(&data);
+                ctx.disable_no_branch_pruning();
+                let mask = (1 << count) - 1;
+                let unpruned = api::solve_subset(&ctx, mask, &config, None, &mut hashbrown::HashMap::new());
+                assert_eq!(pruned.cost, unpruned.cost, "{count} words, {config:?}");
+                assert_eq!(notations(&pruned), notations(&unpruned), "{count} words, {config:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn word_paths_add_up_to_cost() {
+        let data = wordsets::ZODIAC.to_words();
+        for allow_repeat in [false, true] {
+            let sol = minimal_trees(&data, allow_repeat, true, 2);
+            for tree in sol.trees.iter().take(50) {
+                let paths = word_paths(tree, RepeatCost::Free);
+                assert_eq!(paths.len(), data.len());
+                assert_eq!(paths.iter().map(|p| p.hard_nos).max(), Some(sol.cost.hard_nos));
+                assert_eq!(paths.iter().map(|p| p.nos).max(), Some(sol.cost.nos));
+                assert_eq!(paths.iter().map(|p| p.questions()).max(), Some(sol.cost.max_questions));
+                assert_eq!(paths.iter().map(|p| p.hard_nos).sum::<u32>(), sol.cost.sum_hard_nos);
+                assert_eq!(paths.iter().map(|p| p.nos).sum::<u32>(), sol.cost.sum_nos);
+                assert_eq!(paths.iter().map(|p| p.questions()).sum::<u32>(), sol.cost.sum_questions);
+            }
+        }
+    }
+
+    #[test]
+    fn word_paths_table() {
+        let sol = minimal_trees(&words(&["ab", "ac", "b"]), false, true, 2);
+        let table = format_word_paths(&word_paths(&sol.trees[0], RepeatCost::Free));
+        assert_eq!(
+            table,
+            "Word  Questions  No  Hard No  Path\n\
+             B     2          0   0        Contains 'B'? Yes → First letter 'B'? (all No have 'B' second) Yes\n\
+             Ab    2          1   0        Contains 'B'? Yes → First letter 'B'? (all No have 'B' second) No\n\
+             Ac    1          1   1        Contains 'B'? No\n"
+        );
+    }
+
+    #[test]
+    fn script_justifies_soft_misses() {
+        let sol = minimal_trees(&words(&["mia", "nia", "lo"]), false, true, 2);
+        let templates = ScriptTemplates { cues: false, reveal: "It's {word}.".to_string(), ..ScriptTemplates::default() };
+        assert_eq!(
+            format_script(&sol.trees[0], &templates),
+            "I'm getting a letter L somewhere in there…\n\
+             If yes:\n  \
+               It's Lo.\n\
+             If no:\n  \
+               No? Then it's the I, they look alike.\n  \
+               And I'm certain of this one. I'm getting a letter A somewhere in there… \
+               and I'm seeing a strong letter A near the start…\n  \
+               I'm getting a letter M somewhere in there…\n  \
+               If yes:\n    \
+                 It's Mia.\n  \
+               If no:\n    \
+                 No? Then it's the N, they are both nasal sounds.\n    \
+                 It's Nia.\n"
+        );
+    }
+
+    #[test]
+    fn script_reads_yes_split_chains_as_one_line() {
+        let tree: Node = "C:o(leo, +C:i(+L:s(+C:s(C:a(aries, pisces)))))".parse().unwrap();
+        let templates = ScriptTemplates { cues: false, reveal: "It's {word}.".to_string(), ..ScriptTemplates::default() };
+        assert_eq!(
+            format_script(&tree, &templates),
+            "I'm getting a letter O somewhere in there…\n\
+             If yes:\n  \
+               It's Leo.\n\
+             If no:\n  \
+               No? Then let me try something else.\n  \
+               And I'm certain of this one. I'm getting a letter I somewhere in there… \
+               and there's a letter S towards the end, isn't there? \
+               and I'm getting a letter S somewhere in there…\n  \
+               I'm getting a letter A somewhere in there…\n  \
+               If yes:\n    \
+                 It's Aries.\n  \
+               If no:\n    \
+                 No? Then let me try something else.\n    \
+                 It's Pisces.\n"
+        );
+    }
+
+    #[test]
+    fn diagrams_style_edges_by_kind() {
+        let sol = minimal_trees(&words(&["ab", "ac", "b"]), false, true, 2);
+        assert_eq!(
+            sol.trees[0].to_mermaid(),
+            "flowchart TD\n    \
+                n0[\"Contains 'B'?\"]\n    \
+                n1[\"First letter 'B'? (all No have 'B' second)\"]\n    \
+                n2([\"B\"])\n    \
+                n3([\"Ab\"])\n    \
+                n4([\"Ac\"])\n    \
+                n1 -->|Yes| n2\n    \
+                n1 -.->|No| n3\n    \
+                n0 -->|Yes| n1\n    \
+                n0 ==>|No| n4\n"
+        );
+        let dot = sol.trees[0].to_dot();
+        assert!(dot.contains("n0 -> n4 [label=\"No\", color=\"#c62828\", penwidth=2];"));
+        assert!(dot.contains("n1 -> n3 [label=\"No\", color=\"#ef6c00\", style=dashed];"));
+
+        // Merged trees get a choice node per disagreement, Repeats a hexagon
+        let sol = minimal_trees(&words(&["mia", "nia", "lo"]), true, true, 2);
+        let merged = MergedNode::merge(&sol.trees).to_mermaid();
+        assert!(merged.contains("{\"3 options\"}"));
+        assert!(merged.contains("{{\"Repeat Lo\"}}"));
+        assert!(MergedNode::merge(&sol.trees).to_dot().contains("shape=diamond"));
+    }
+
+    #[test]
+    fn svg_follows_spine_layout() {
+        let sol = minimal_trees(&words(&["ab", "ac", "b"]), false, true, 2);
+        let svg = render_svg(&sol.trees[0], &SvgOptions::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<text").count(), 5);
+        // One hard and one soft No elbow, two Yes spine segments
+        assert_eq!(svg.matches("stroke=\"#c62828\"").count(), 1);
+        assert_eq!(svg.matches("stroke-dasharray").count(), 1);
+        assert_eq!(svg.matches("<line").count(), 2);
+        assert!(svg.contains("font-weight=\"bold\">Ab</text>"));
+
+        let options = SvgOptions { page_size: Some(SvgOptions::A4), show_word_sets: true, ..SvgOptions::default() };
+        let svg = render_svg(&sol.trees[0], &options);
+        assert!(svg.contains("width=\"595\" height=\"842\" viewBox=\"0 0 "));
+        assert!(svg.contains(">{B, Ab, Ac}</text>"));
+    }
+
+    #[test]
+    fn notation_round_trips() {
+        use node::Position;
+
+        let positions = [
+            Position::Contains,
+            Position::First,
+            Position::Second,
+            Position::Third,
+            Position::ThirdToLast,
+            Position::SecondToLast,
+            Position::Last,
+            Position::Double,
+            Position::Triple,
+        ];
+        for position in positions {
+            assert_eq!(Position::from_code(position.code()), Some(position));
+        }
+
+        let text = "C:r(F:a/L:a(aries, +D:s/D:l(*leo(\"a b\"))), TTL:i/STL:e(TR:o(STL:x(x, y), z), \"*q\"))";
+        let tree: Node = text.parse().unwrap();
+        assert_eq!(tree.to_string(), text);
+        assert!(matches!(&tree, Node::PositionalSplit { test_position: Position::Contains, .. }));
+        assert_eq!(" C:r ( a ,b ) ".parse::<Node>().unwrap().to_string(), "C:r(a, b)");
+
+        // Every optimal zodiac tree survives a round trip
+        let data = words(&["aries", "taurus", "gemini", "cancer", "leo", "virgo", "libra", "scorpio"]);
+        for tree in minimal_trees(&data, true, true, 2).trees {
+            assert_eq!(tree.to_string().parse::<Node>().unwrap(), *tree);
+        }
+
+        let err = "C:r(a, Q:b(c, d))".parse::<Node>().unwrap_err();
+        assert_eq!(err.to_string(), "unknown position code 'Q' at byte 7");
+        assert_eq!("C:r(a)".parse::<Node>().unwrap_err().offset, 5);
+        assert!("a b".parse::<Node>().is_err());
+    }
+
+    #[test]
+    fn saved_trees_validate_and_keep_their_cost() {
+        let data = words(&["aries", "taurus", "gemini", "cancer", "leo", "virgo", "libra", "scorpio", "pisces"]);
+        for allow_repeat in [false, true] {
+            for redeeming_yes in [0, 2] {
+                let sol = minimal_trees(&data, allow_repeat, true, redeeming_yes);
+                for tree in &sol.trees {
+                    assert_eq!(validate_tree(tree, Some(&data)), vec![]);
+                    assert_eq!(tree_cost(tree, redeeming_yes), sol.cost);
+                }
+            }
+        }
+
+        let tree: Node = "C:e(L:o(aries, leo), *virgo(S:e/F:e(gemini, leo)))".parse().unwrap();
+        let issues: Vec<String> =
+            validate_tree(&tree, Some(&words(&["aries", "leo", "virgo"]))).iter().map(|i| i.to_string()).collect();
+        assert_eq!(
+            issues,
+            [
+                "'leo' appears more than once",
+                "'gemini' is not in the word list",
+                "'gemini' is on the No side of \"Contains 'E'?\" but answers Yes",
+                "'leo' is on the No side of \"Contains 'E'?\" but answers Yes",
+                "'aries' is on the Yes side of \"Last letter 'O'?\" but answers No",
+                "'leo' is on the No side of \"Last letter 'O'?\" but answers Yes",
+                "'leo' is on the No side of \"Second letter 'E'? (all No have 'E' first)\" but answers Yes",
+            ]
+        );
+    }
+
+    #[test]
+    fn word_sets_are_solvable_lists() {
+        assert_eq!(wordsets::by_name("Chinese_Zodiac"), Some(&wordsets::CHINESE_ZODIAC));
+        assert_eq!(wordsets::by_name(" card values "), Some(&wordsets::CARD_VALUES));
+        assert_eq!(wordsets::by_name("pokemon"), None);
+
+        for set in wordsets::ALL {
+            assert_eq!(wordsets::by_name(set.name), Some(set));
+            assert!(set.words.len() <= 32, "{} is too large for the solver", set.name);
+            let unique: std::collections::HashSet<_> = set.words.iter().collect();
+            assert_eq!(unique.len(), set.words.len(), "{} repeats a word", set.name);
+            assert!(set.words.iter().all(|w| w.chars().all(|c| c.is_ascii_lowercase() || c == ' ')));
+        }
+        let sol = minimal_trees(&wordsets::SUITS.to_words(), false, true, 2);
+        assert!(!sol.is_unsolvable());
+    }
+
+    #[test]
+    fn batch_reports_each_line() {
+        let input = r#"
+            {"id": "a", "words": ["aries", "taurus", "gemini", "leo", "virgo"]}
+            # comments and blank lines are skipped
+
+            {"id": "b", "words": ["aries", "taurus", "gemini", "leo", "virgo"], "config": {"max_depth": 2}}
+            {"set": "suits", "config": {"allow_repeat": true}}
+            {"words": ["leo"], "config": {"max_dept": 2}}
+            {"words": "leo"}
+        "#;
+        let results = solve_jsonl(input, &SolverConfig::default());
+        assert_eq!(results.iter().map(|r| r.line).collect::<Vec<_>>(), [2, 5, 6, 7, 8]);
+
+        let expected = minimal_trees(&words(&["aries", "taurus", "gemini", "leo", "virgo"]), false, false, 2);
+        assert_eq!(results[0].id, Some(serde_json::json!("a")));
+        assert_eq!(results[0].cost, Some(expected.cost));
+        assert_eq!(results[0].tree_count, expected.trees.len());
+        assert_eq!(results[0].tree, Some(expected.trees[0].to_string()));
+
+        assert_eq!(results[1].error.as_deref(), Some("no tree fits within 2 questions (minimum feasible depth: 3)"));
+        assert_eq!(results[2].words, wordsets::SUITS.to_words());
+        assert!(results[2].error.is_none());
+        assert_eq!(results[3].error.as_deref(), Some("unknown config field 'max_dept'"));
+        assert!(results[4].error.as_deref().unwrap().starts_with("invalid request"));
+    }
+
+    #[test]
+    fn design_finds_the_cheapest_subsets() {
+        let pool = wordsets::ZODIAC.to_words();
+        let config = SolverConfig::new(false, true, 2);
+        let options = DesignOptions { required: words(&["leo"]), top: 3, ..DesignOptions::new(4) };
+        let best = design_subsets(&pool, &options, &config).unwrap();
+        assert_eq!(best.len(), 3);
+
+        // Same costs as solving each subset on its own, and nothing cheaper anywhere
+        for candidate in &best {
+            assert_eq!(candidate.words.len(), 4);
+            assert!(candidate.words.contains(&"leo".to_string()));
+            assert_eq!(minimal_trees_with_config(&candidate.words, &config).cost, candidate.solution.cost);
+        }
+        for pair in best.windows(2) {
+            assert!(config.compare(&pair[0].solution.cost, &pair[1].solution.cost).is_le());
+        }
+        let others: Vec<&String> = pool.iter().filter(|w| *w != "leo").collect();
+        for a in 0..others.len() {
+            for b in a + 1..others.len() {
+                for c in b + 1..others.len() {
+                    let subset = vec!["leo".to_string(), others[a].clone(), others[b].clone(), others[c].clone()];
+                    let cost = minimal_trees_with_config(&subset, &config).cost;
+                    assert!(config.compare(&best[0].solution.cost, &cost).is_le());
+                }
+            }
+        }
+
+        let err = design_subsets(&pool, &DesignOptions { required: words(&["ophiuchus"]), ..options }, &config);
+        assert_eq!(err.unwrap_err(), DesignError::UnknownRequired("ophiuchus".to_string()));
+        assert!(design_subsets(&pool, &DesignOptions::new(13), &config).is_err());
+        // 11 choose 3 subsets, checked before any is solved
+        let err = design_subsets(&pool, &DesignOptions { max_combinations: 164, ..options }, &config);
+        assert_eq!(err.unwrap_err(), DesignError::TooManyCombinations { count: 165, limit: 164 });
+    }
+
+    #[test]
+    fn sensitivity_matches_solving_each_variant() {
+        let list = words(&["aries", "taurus", "gemini", "cancer", "leo", "virgo"]);
+        let config = SolverConfig::new(false, true, 2);
+        let report = sensitivity(&list, Some("libra"), &config).unwrap();
+        assert_eq!(report.cost, Some(minimal_trees_with_config(&list, &config).cost));
+        assert_eq!(report.words.len(), list.len());
+
+        for impact in &report.words {
+            let without: Vec<String> = list.iter().filter(|w| **w != impact.word).cloned().collect();
+            assert_eq!(impact.without, Some(minimal_trees_with_config(&without, &config).cost));
+            let swapped: Vec<String> =
+                list.iter().map(|w| if *w == impact.word { "libra".to_string() } else { w.clone() }).collect();
+            assert_eq!(impact.replaced, Some(minimal_trees_with_config(&swapped, &config).cost));
+        }
+        for pair in report.words.windows(2) {
+            assert!(config.compare(pair[0].without.as_ref().unwrap(), pair[1].without.as_ref().unwrap()).is_le());
+        }
+
+        assert_eq!(sensitivity(&words(&["leo"]), None, &config), Err(SensitivityError::TooFewWords));
+        let err = sensitivity(&list, Some("leo"), &config).unwrap_err();
+        assert_eq!(err, SensitivityError::DuplicateWord("leo".to_string()));
+    }
+
+    #[test]
+    fn sensitivity_accepts_a_full_mask() {
+        // 32 words fill every bit of the mask; a cap of 4 questions rules out every variant cheaply
+        let list: Vec<String> =
+            (0..32u8).map(|idx| format!("{}{}", (b'a' + idx / 26) as char, (b'a' + idx % 26) as char)).collect();
+        let config = SolverConfig { max_depth: Some(4), ..SolverConfig::new(false, true, 2) };
+        let report = sensitivity(&list, None, &config).unwrap();
+        assert_eq!(report.cost, None);
+        assert_eq!(report.words.len(), 32);
+        assert!(report.words.iter().all(|impact| impact.without.is_none() && impact.replaced.is_none()));
+    }
+
+    #[test]
+    fn aliases_reach_their_word() {
+        // Follow a spectator's spelling through the questions, `None` if it misses a soft split's
+        // requirement on the way; Repeat asks for the canonical word
+        fn route<'a>(node: &'a Node, spelling: &str, word: &str) -> Option<&'a str> {
+            match node {
+                Node::Leaf(leaf) => Some(leaf),
+                Node::Repeat { word: asked, no } if asked == word => Some(asked),
+                Node::Repeat { no, .. } => route(no, spelling, word),
+                Node::PositionalSplit {
+                    test_letter, test_position, requirement_letter, requirement_position, yes, no
+                } => {
+                    if validate::word_matches(spelling, *test_letter, *test_position) {
+                        route(yes, spelling, word)
+                    } else if (test_letter, test_position) == (requirement_letter, requirement_position)
+                        || validate::word_matches(spelling, *requirement_letter, *requirement_position)
+                    {
+                        route(no, spelling, word)
+                    } else {
+                        None
+                    }
+                }
+                Node::YesSplit { yes, .. } => route(yes, spelling, word),
+            }
+        }
+
+        let list = words(&["aries", "leo", "scorpio", "sagittarius", "aquarius", "pisces"]);
+        let aliases =
+            [vec![], vec![], words(&["scorpion"]), words(&["sagitarius"]), words(&["aquarious"]), words(&["piscis"])];
+        for allow_repeat in [false, true] {
+            let config = SolverConfig::new(allow_repeat, true, 2);
+            let plain = minimal_trees_with_config(&list, &config);
+            let sol = minimal_trees_with_aliases(&list, &aliases, &config);
+            assert!(!sol.is_unsolvable());
+            assert!(config.compare(&plain.cost, &sol.cost).is_le());
+            for tree in &sol.trees {
+                assert!(validate_tree(tree, Some(&list)).is_empty());
+                for (idx, word) in list.iter().enumerate() {
+                    for spelling in std::iter::once(word).chain(aliases.get(idx).into_iter().flatten()) {
+                        assert_eq!(route(tree, spelling, word), Some(word.as_str()), "{spelling} in {tree}");
+                    }
+                }
+            }
+        }
+        // Without aliases, some optimal tree claims a letter that a misspelling lacks
+        let config = SolverConfig::new(false, true, 2);
+        let plain = minimal_trees_with_config(&list, &config);
+        assert!(plain.trees.iter().any(|tree| route(tree, "piscis", "pisces") != Some("pisces")));
+        // No aliases at all is the plain solver
+        assert_eq!(minimal_trees_with_aliases(&list, &[], &config).cost, plain.cost);
+    }
+
+    #[test]
+    fn step_solver_matches_a_single_solve() {
+        let list = words(&["aries", "taurus", "gemini", "cancer", "leo", "virgo", "libra"]);
+        let config = SolverConfig::new(true, true, 2);
+        let expected = minimal_trees_with_config(&list, &config);
+
+        let mut solver = StepSolver::new(list.clone(), config.clone());
+        let mut steps = 0;
+        let mut last = solver.progress();
+        while !last.done {
+            let progress = solver.step(20);
+            assert!(progress.expanded > last.expanded || progress.done);
+            last = progress;
+            steps += 1;
+        }
+        assert!(steps > 1, "the budget should force several steps");
+        let sol = solver.result().unwrap().as_ref().unwrap();
+        assert_eq!(sol.cost, expected.cost);
+        assert_eq!(sol.trees, expected.trees);
+
+        // An infeasible depth cap reports the same minimum depth as the blocking solver
+        let capped = SolverConfig { max_depth: Some(2), ..config };
+        let mut solver = StepSolver::new(list.clone(), capped.clone());
+        while !solver.step(50).done {}
+        assert_eq!(solver.result().unwrap().as_ref().unwrap_err(), &try_minimal_trees(&list, &capped).unwrap_err());
+
+        let mut solver = StepSolver::new(list, SolverConfig::default());
+        solver.step(10);
+        solver.cancel();
+        let progress = solver.step(1_000_000);
+        assert!(progress.cancelled && !progress.done);
+        assert!(solver.result().is_none());
+    }
+
+    #[test]
+    fn merged_options_summarize_their_trees() {
+        fn letters(node: &Node, out: &mut std::collections::BTreeSet<char>) {
+            match node {
+                Node::Leaf(_) => {}
+                Node::Repeat { no, .. } => letters(no, out),
+                Node::PositionalSplit { test_letter, requirement_letter, yes, no, .. } => {
+                    out.extend([*test_letter, *requirement_letter]);
+                    letters(yes, out);
+                    letters(no, out);
+                }
+                Node::YesSplit { test_letter, requirement_letter, yes, .. } => {
+                    out.extend([*test_letter, *requirement_letter]);
+                    letters(yes, out);
+                }
+            }
+        }
+        // Children split the option's trees between them
+        fn check_counts(node: &MergedNode) {
+            for option in &node.options {
+                for child in option.yes_branch.iter().chain(option.no_branch.iter()) {
+                    assert_eq!(child.options.iter().map(|o| o.tree_count).sum::<usize>(), option.tree_count);
+                    check_counts(child);
+                }
+            }
+        }
+
+        let list = words(&["aries", "taurus", "gemini", "cancer", "leo"]);
+        let sol = minimal_trees(&list, true, false, 2);
+        let merged = MergedNode::merge(&sol.trees);
+        assert!(merged.is_choice());
+        assert_eq!(merged.options.iter().map(|o| o.tree_count).sum::<usize>(), sol.trees.len());
+        check_counts(&merged);
+        assert_eq!(merged.max_depth(), sol.cost.max_questions);
+
+        for option in &merged.options {
+            let trees: Vec<&NodeRef> =
+                sol.trees.iter().filter(|tree| merged::NodeInfo::from_node(tree) == option.info).collect();
+            assert_eq!(trees.len(), option.tree_count);
+            let mut expected_words = option.words.clone();
+            expected_words.sort();
+            let mut sorted = list.clone();
+            sorted.sort();
+            assert_eq!(expected_words, sorted);
+            let mut expected_letters = std::collections::BTreeSet::new();
+            for tree in &trees {
+                letters(tree, &mut expected_letters);
+            }
+            assert_eq!(option.letters, expected_letters.into_iter().collect::<String>());
+            let deepest =
+                trees.iter().flat_map(|tree| word_paths(tree, RepeatCost::Free)).map(|path| path.questions()).max();
+            assert_eq!(Some(option.max_depth), deepest);
+        }
+    }
+
+    /// The merged node a resolution's choice path leads to
+    fn merged_at<'a>(root: &'a MergedNode, res: &Resolution, path: &[Branch]) -> &'a MergedNode {
+        let mut node = root;
+        for depth in 0..path.len() {
+            let selected = res.choices.iter().find(|c| c.path == path[..depth]).map_or(0, |c| c.selected);
+            let option = &node.options[selected];
+            node = match path[depth] {
+                Branch::Yes => option.yes_branch.as_deref().unwrap(),
+                Branch::No => option.no_branch.as_deref().unwrap(),
+            };
+        }
+        node
+    }
+
+    #[test]
+    fn merge_shares_identical_subtrees() {
+        // Merged nodes reachable by walking options, counting shared ones at each occurrence
+        fn walked(node: &MergedNode) -> usize {
+            let children = node.options.iter().flat_map(|o| o.yes_branch.iter().chain(o.no_branch.iter()));
+            1 + children.map(|child| walked(child)).sum::<usize>()
+        }
+
+        let list = words(&["aries", "taurus", "gemini", "cancer", "leo", "virgo"]);
+        let sol = minimal_trees(&list, true, false, 2);
+        let merged = MergedNode::merge(&sol.trees);
+        let json = serde_json::to_value(&merged).unwrap();
+        let nodes = json["nodes"].as_array().unwrap();
+        assert!(nodes.len() < walked(&merged));
+
+        // Rebuilding the nested structure from the references gives back the same options
+        fn check(merged: &MergedNode, nodes: &[serde_json::Value], id: usize) {
+            let options = nodes[id]["options"].as_array().unwrap();
+            assert_eq!(options.len(), merged.options.len());
+            for (option, json) in merged.options.iter().zip(options) {
+                assert_eq!(json["info"], serde_json::to_value(&option.info).unwrap());
+                assert_eq!(json["treeCount"], option.tree_count);
+                for (child, key) in [(&option.yes_branch, "yesBranch"), (&option.no_branch, "noBranch")] {
+                    match child {
+                        Some(child) => check(child, nodes, json[key].as_u64().unwrap() as usize),
+                        None => assert!(json.get(key).is_none()),
+                    }
+                }
+            }
+        }
+        check(&merged, nodes, 0);
+        // Options are in structural order
+        assert!(merged.options.windows(2).all(|pair| pair[0].info < pair[1].info));
+    }
+
+    #[test]
+    fn resolve_follows_picks_through_the_merge() {
+        let list = words(&["aries", "taurus", "gemini", "cancer", "leo"]);
+        let sol = minimal_trees(&list, true, false, 2);
+        let merged = MergedNode::merge(&sol.trees);
+        assert!(merged.options.len() > 1);
+
+        // Without picks the first tree comes out, and every option of every choice node is valid
+        let default = merged.resolve(&sol.trees, &[]).unwrap();
+        assert_eq!(default.tree, *sol.trees[0]);
+        for choice in &default.choices {
+            assert_eq!(choice.selected, 0);
+            assert_eq!(choice.valid.len(), merged_at(&merged, &default, &choice.path).options.len());
+            assert!(choice.valid.iter().enumerate().all(|(idx, &valid)| idx == valid));
+        }
+
+        for (idx, option) in merged.options.iter().enumerate() {
+            // Each option of each reachable choice node resolves to a merged tree that uses it
+            let picked = merged.resolve(&sol.trees, &[(vec![], idx)]).unwrap();
+            assert_eq!(NodeInfo::from_node(&picked.tree), option.info);
+            for choice in &picked.choices {
+                let options = merged_at(&merged, &picked, &choice.path).options.len();
+                for nested in 0..options {
+                    let res = merged.resolve(&sol.trees, &[(choice.path.clone(), nested), (vec![], idx)]).unwrap();
+                    assert!(sol.trees.iter().any(|tree| **tree == res.tree));
+                    let chosen = res.choices.iter().find(|c| c.path == choice.path).unwrap();
+                    assert_eq!(chosen.selected, nested);
+                }
+            }
+            // A later pick that contradicts an earlier one is ignored
+            let other = (idx + 1) % merged.options.len();
+            let res = merged.resolve(&sol.trees, &[(vec![], idx), (vec![], other)]).unwrap();
+            assert_eq!(res.tree, picked.tree);
+            assert_eq!(res.ignored, vec![Vec::<Branch>::new()]);
+        }
+        assert!(MergedNode::merge(&[]).resolve(&[], &[]).is_none());
+    }
+
+    #[test]
+    fn diff_aligns_trees_by_path() {
+        let before: Node = "C:r/C:e(C:t/C:i(taurus, aries), leo)".parse().unwrap();
+        assert!(diff(&before, &before, RepeatCost::Free).is_empty());
+
+        // The Yes subtree moves under a new root question
+        let after: Node = "C:o/C:a(leo, C:t/C:i(taurus, aries))".parse().unwrap();
+        let changes = diff(&before, &after, RepeatCost::Free);
+        assert_eq!(changes.changed.len(), 1);
+        assert!(changes.changed[0].path.is_empty());
+        assert_eq!(changes.changed[0].before, NodeInfo::from_node(&before));
+        let expected =
+            MovedSubtree { from: vec![Branch::Yes], to: vec![Branch::No], words: words(&["taurus", "aries"]) };
+        assert_eq!(changes.moved, vec![expected]);
+        // Word changes agree with the word paths of both trees
+        let (old, new) = (word_paths(&before, RepeatCost::Free), word_paths(&after, RepeatCost::Free));
+        for change in &changes.words {
+            let old = old.iter().find(|path| path.word == change.word).unwrap();
+            let new = new.iter().find(|path| path.word == change.word).unwrap();
+            assert_eq!((change.before.nos, change.after.nos), (old.nos, new.nos));
+            assert_eq!((change.before.questions, change.after.questions), (old.questions(), new.questions()));
+        }
+        assert_eq!(changes.words.len(), 3);
+        let text = format_diff(&changes, &after);
+        assert!(text.contains("[moved from Yes]"), "{text}");
+
+        // A changed question below an unchanged one, and a replaced word
+        let after: Node = "C:r/C:e(F:a/S:a(aries, virgo), leo)".parse().unwrap();
+        let changes = diff(&before, &after, RepeatCost::Free);
+        let paths: Vec<Vec<Branch>> = changes.changed.iter().map(|change| change.path.clone()).collect();
+        assert_eq!(paths, vec![vec![Branch::Yes]]);
+        assert!(changes.moved.is_empty());
+        assert_eq!((changes.removed, changes.added), (words(&["taurus"]), words(&["virgo"])));
+    }
+
+    #[test]
+    fn partition_classes_split_words_alike() {
+        // Words on each side of every node, in pre-order: what a class's trees share
+        fn partitions(node: &Node) -> Vec<Vec<String>> {
+            let mut out = vec![word_paths(node, RepeatCost::Free).into_iter().map(|path| path.word).collect()];
+            for branch in [Branch::Yes, Branch::No] {
+                if let Some(child) = merged::child(node, branch) {
+                    out.extend(partitions(child));
+                }
+            }
+            out
+        }
+
+        let list = words(&["aries", "taurus", "gemini", "cancer", "leo", "virgo"]);
+        let sol = minimal_trees(&list, true, false, 2);
+        let classes = group_by_partition(&sol.trees);
+        assert!(classes.len() < sol.trees.len());
+        assert_eq!(classes.iter().map(|class| class.tree_count).sum::<usize>(), sol.trees.len());
+        assert_eq!(sol.one_per_partition().trees.len(), classes.len());
+
+        for (idx, class) in classes.iter().enumerate() {
+            let members: Vec<&NodeRef> =
+                sol.trees.iter().filter(|tree| partitions(tree) == partitions(&class.tree)).collect();
+            assert_eq!(members.len(), class.tree_count);
+            assert!(std::rc::Rc::ptr_eq(members[0], &class.tree));
+            assert!(classes[..idx].iter().all(|other| partitions(&other.tree) != partitions(&class.tree)));
+            // Every member's questions are among the alternatives, which include nothing else
+            for alternative in &class.alternatives {
+                let asked: std::collections::BTreeSet<NodeInfo> = members
+                    .iter()
+                    .map(|tree| alternative.path.iter().fold(&***tree, |node, &b| merged::child(node, b).unwrap()))
+                    .map(NodeInfo::from_node)
+                    .collect();
+                assert_eq!(asked.into_iter().collect::<Vec<_>>(), alternative.questions);
+            }
+        }
+    }
+
+    #[test]
+    fn naturalness_prefers_plain_questions() {
+        let weights = NaturalnessWeights::default();
+        let score = |tree: &str| naturalness(&tree.parse::<Node>().unwrap(), &weights);
+        // Contains beats a positional question, and a common letter beats a rare one
+        assert!(score("C:e(leo, ox)") < score("L:o(leo, ox)"));
+        assert!(score("C:e(leo, ox)") < score("C:x(ox, leo)"));
+        // A soft split costs more at the root than deeper down
+        assert!(score("C:r/C:e(C:t(taurus, aries), leo)") > score("C:r(C:t/C:i(taurus, aries), leo)"));
+    }
+
+    #[test]
+    fn trees_are_ranked_by_naturalness() {
+        let weights = NaturalnessWeights::default();
+        let list = words(&["aries", "taurus", "gemini", "cancer", "leo", "virgo"]);
+        // Ranking is opt-in, so the plain configuration keeps the search order
+        let unranked_config = SolverConfig::new(true, true, 2);
+        assert_eq!(unranked_config.naturalness, None);
+        let unranked = minimal_trees_with_config(&list, &unranked_config);
+        let ranked_config = SolverConfig { naturalness: Some(weights), ..unranked_config };
+        let sol = minimal_trees_with_config(&list, &ranked_config);
+        let scores: Vec<f64> = sol.trees.iter().map(|tree| naturalness(tree, &weights)).collect();
+        assert!(scores.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(scores[0] < naturalness(&unranked.trees[0], &weights));
+        // Ranking only reorders the trees
+        let notation = |sol: &Solution| {
+            let mut trees: Vec<String> = sol.trees.iter().map(|tree| tree.to_string()).collect();
+            trees.sort();
+            trees
+        };
+        assert_eq!(notation(&sol), notation(&unranked));
+    }
+
+    #[test]
+    fn query_filters_trees() {
+        let list = words(&["aries", "taurus", "gemini", "cancer", "leo"]);
+        let sol = minimal_trees(&list, true, false, 2);
+        let count = |query: &str| sol.filter(&query.parse::<Query>().unwrap(), RepeatCost::Free).trees.len();
+
+        let hard_nos =
+            |tree: &Node, word: &str| {
+                word_paths(tree, RepeatCost::Free).into_iter().find(|p| p.word == word).unwrap().hard_nos
+            };
+        let by_hand = sol.trees.iter().filter(|tree| hard_nos(tree, "leo") == 0).count();
+        assert_eq!(count("word:leo.hard_nos=0"), by_hand);
+        assert_eq!(count("word:LEO.hard_nos=0 or not word:leo.hard_nos=0"), sol.trees.len());
+        assert_eq!(count("any:repeat") + count("never:repeat"), sol.trees.len());
+        assert_eq!(count("root:leaf"), 0);
+        assert_eq!(count("at:root:split"), count("root:split"));
+        assert_eq!(count("root:hard"), count("root:split and not root:soft") + count("root:yes-split and root:hard"));
+        assert_eq!(
+            count("(any:contains or any:last) and any:letter:E"),
+            count("any:letter:e and (any:contains or any:last)")
+        );
+
+        let query: Query = "root:contains and not (at:yes.no:leaf or word:leo.nos>=2)".parse().unwrap();
+        let Query::And(terms) = &query else { panic!("expected a conjunction") };
+        assert_eq!(terms[0], Query::Root(NodeKind::Position(node::Position::Contains)));
+        let filtered = sol.filter(&query, RepeatCost::Free);
+        assert_eq!(filtered.cost, sol.cost);
+        assert!(filtered.trees.iter().all(|tree| query.matches(tree, RepeatCost::Free)));
+
+        let invalid = ["", "root:", "root:bogus", "at:up:leaf", "word:leo.nos", "word:leo.nos<x", "(root:leaf", "leaf or"];
+        for bad in invalid {
+            assert!(bad.parse::<Query>().is_err(), "{bad:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn repeat_budget_and_cost_are_configurable() {
+        fn most_repeats(node: &Node) -> u32 {
+            match node {
+                Node::Leaf(_) => 0,
+                Node::Repeat { no, .. } => 1 + most_repeats(no),
+                Node::PositionalSplit { yes, no, .. } => most_repeats(yes).max(most_repeats(no)),
+                Node::YesSplit { yes, .. } => most_repeats(yes),
+            }
+        }
+
+        let list = words(&["aries", "taurus", "gemini", "cancer", "leo", "virgo"]);
+        let solve = |max_repeats, repeat_cost| {
+            let config = SolverConfig { max_repeats, repeat_cost, ..SolverConfig::new(true, false, 2) };
+            minimal_trees_with_config(&list, &config)
+        };
+        let once = solve(1, RepeatCost::Free);
+        let twice = solve(2, RepeatCost::Free);
+        assert!(compare_costs(&twice.cost, &once.cost, false) == std::cmp::Ordering::Less);
+        assert!(twice.trees.iter().all(|tree| most_repeats(tree) <= 2));
+        assert!(twice.trees.iter().any(|tree| most_repeats(tree) == 2));
+        assert!(solve(0, RepeatCost::Free).trees.iter().all(|tree| most_repeats(tree) == 0));
+
+        for repeat_cost in [RepeatCost::Free, RepeatCost::SoftNo, RepeatCost::HardNo] {
+            let sol = solve(2, repeat_cost);
+            for tree in &sol.trees {
+                assert_eq!(tree_cost_with_repeats(tree, 2, repeat_cost), sol.cost);
+                // Word paths count a Repeat's No edge like the solver did
+                let paths = word_paths(tree, repeat_cost);
+                assert_eq!(paths.iter().map(|p| p.nos).max(), Some(sol.cost.nos));
+                assert_eq!(paths.iter().map(|p| p.hard_nos).max(), Some(sol.cost.hard_nos));
+                assert_eq!(paths.iter().map(|p| p.nos).sum::<u32>(), sol.cost.sum_nos);
+                assert_eq!(paths.iter().map(|p| p.hard_nos).sum::<u32>(), sol.cost.sum_hard_nos);
+            }
+        }
+        let tree: Node = "*leo(C:a(aries, virgo))".parse().unwrap();
+        let counts = |repeat_cost| -> Vec<(u32, u32)> {
+            word_paths(&tree, repeat_cost).iter().map(|p| (p.nos, p.hard_nos)).collect()
+        };
+        assert_eq!(counts(RepeatCost::Free), [(0, 0), (0, 0), (1, 1)]);
+        assert_eq!(counts(RepeatCost::SoftNo), [(0, 0), (1, 0), (2, 1)]);
+        assert_eq!(counts(RepeatCost::HardNo), [(0, 0), (1, 1), (2, 2)]);
+        // A Repeat costing a No saves nothing over a split, so the Nos match the plain solve
+        let plain = minimal_trees(&list, false, false, 2);
+        let costly = solve(2, RepeatCost::HardNo);
+        assert_eq!((costly.cost.hard_nos, costly.cost.nos), (plain.cost.hard_nos, plain.cost.nos));
+    }
+
     #[test]
     fn repeat_beats_depth_for_two_words() {
         use std::cmp::Ordering;
@@ -87,6 +1067,10 @@ mod tests {
                 redeemed_sum_hard_nos: 2,
                 sum_nos: 2,
                 redeemed_sum_nos: 4,
+                max_consecutive_nos: 1,
+                leading_nos: 1,
+                max_questions: 2,
+                sum_questions: 5,
                 word_count: 3
             }
         );
@@ -124,6 +1108,10 @@ mod tests {
                 redeemed_sum_hard_nos: 0,
                 sum_nos: 14,
                 redeemed_sum_nos: 0,
+                max_consecutive_nos: 2,
+                leading_nos: 2,
+                max_questions: 6,
+                sum_questions: 52,
                 word_count: 12
             }
         );
@@ -140,6 +1128,10 @@ mod tests {
                 redeemed_sum_hard_nos: 0,
                 sum_nos: 17,
                 redeemed_sum_nos: 0,
+                max_consecutive_nos: 2,
+                leading_nos: 2,
+                max_questions: 5,
+                sum_questions: 48,
                 word_count: 12
             }
         );
@@ -177,6 +1169,10 @@ mod tests {
                 redeemed_sum_hard_nos: 4,
                 sum_nos: 14,
                 redeemed_sum_nos: 22,
+                max_consecutive_nos: 2,
+                leading_nos: 2,
+                max_questions: 8,
+                sum_questions: 64,
                 word_count: 12
             }
         );
@@ -193,6 +1189,10 @@ mod tests {
                 redeemed_sum_hard_nos: 8,
                 sum_nos: 17,
                 redeemed_sum_nos: 32,
+                max_consecutive_nos: 2,
+                leading_nos: 2,
+                max_questions: 5,
+                sum_questions: 52,
                 word_count: 12
             }
         );
@@ -227,6 +1227,10 @@ mod tests {
                 redeemed_sum_hard_nos: 0,
                 sum_nos: 2,
                 redeemed_sum_nos: 4,
+                max_consecutive_nos: 1,
+                leading_nos: 1,
+                max_questions: 2,
+                sum_questions: 5,
                 word_count: 3
             },
             "Expected all-soft separation; got {:?}",
@@ -250,6 +1254,10 @@ mod tests {
                 redeemed_sum_hard_nos: 0,
                 sum_nos: 1,
                 redeemed_sum_nos: 2,
+                max_consecutive_nos: 1,
+                leading_nos: 1,
+                max_questions: 1,
+                sum_questions: 2,
                 word_count: 2
             }
         );
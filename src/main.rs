@@ -1,60 +1,754 @@
-use anagram_design::{format_tree, minimal_trees};
-
-fn zodiac_words() -> Vec<String> {
-    vec![
-        "aries",
-        "taurus",
-        "gemini",
-        "cancer",
-        "leo",
-        "virgo",
-        "libra",
-        "scorpio",
-        "sagittarius",
-        "capricorn",
-        "aquarius",
-        "pisces",
-    ]
-    .into_iter()
-    .map(|s| s.to_string())
-    .collect()
-}
-
-fn print_solutions(allow_repeat: bool, prioritize_soft_no: bool) {
-    let words = zodiac_words();
-    let word_count = words.len() as u32;
-    const DISPLAY: usize = 5;
-    let result = minimal_trees(&words, allow_repeat, prioritize_soft_no, 2);
-    let preview = DISPLAY.min(result.trees.len());
-    let avg_sum_hard = result.cost.sum_hard_nos as f32 / word_count as f32;
-    let avg_sum = result.cost.sum_nos as f32 / word_count as f32;
-    println!(
-        "Allow repeat: {} | Prioritize soft no: {} | Best cost = (max hard no {}, max no {}, avg hard no {:.1}, avg no {:.1}) | {} tree(s)",
-        allow_repeat,
-        prioritize_soft_no,
-        result.cost.hard_nos,
-        result.cost.nos,
-        avg_sum_hard,
-        avg_sum,
-        result.trees.len()
-    );
-    for (idx, tree) in result.trees.iter().take(preview).enumerate() {
-        let tree_output = format_tree(tree);
-        let tree_num = idx + 1;
-        println!("--- Tree {tree_num} ---\n{tree_output}");
-    }
-    if result.trees.len() > preview {
-        let more = result.trees.len() - preview;
-        println!("... {more} more optimal tree(s) omitted from display");
-    }
-}
-
-fn main() {
-    print_solutions(true, false);
-    println!();
-    print_solutions(true, true);
-    println!();
-    print_solutions(false, false);
-    println!();
-    print_solutions(false, true);
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Read};
+use std::process::ExitCode;
+use std::rc::Rc;
+
+use anagram_design::{
+    design_subsets, diff, format_alternatives, format_diff, format_script, format_tree, format_word_paths,
+    group_by_partition, render_svg, sensitivity, tree_cost_with_repeats, try_minimal_trees_with_aliases, validate_tree,
+    word_paths, wordsets, BatchRequest, BatchSolver, Cost, CostWeights, DesignOptions, MergedNode, NaturalnessWeights,
+    Node, Query, RepeatCost, ScriptTemplates, Solution, SolverConfig, SvgOptions,
+};
+
+const USAGE: &str = "\
+Usage: anagram_design <command> [options]
+
+Commands:
+  solve [WORDS...]     Find the optimal trees for a word list
+                       (words from arguments, --input FILE, or stdin); write
+                       scorpio=scorpion to also accept alternate spellings
+  render [TREE]        Render a saved tree, written in compact notation
+                       (from the argument, --input FILE, or stdin)
+  validate [TREE]      Check that a saved tree sorts its words correctly
+  diff [BEFORE AFTER]  Compare two trees in compact notation (from the arguments, or one per
+                       line of --input FILE or stdin): changed questions, moved subtrees
+                       and words whose path changed; ascii annotates the second tree
+  batch                Solve one word list per line (from --input FILE or stdin): either plain
+                       words or a JSON object such as {\"words\": [...], \"config\": {...}}
+                       (or {\"set\": \"zodiac\"}), whose config overrides the flags
+  design [WORDS...]    Pick the --size words from a candidate pool with the cheapest trees
+                       (pool from arguments, --set, --words, --input FILE, or stdin)
+  sensitivity [WORDS...]
+                       Re-solve with each word removed (and with --replace-with), ranking
+                       the words by how much cheaper the set gets without them
+  sets                 List the built-in word sets
+
+Solver options (solve, batch, design, sensitivity, validate --optimal):
+  --repeat                   Allow the \"repeat in your mind\" ploy
+  --max-repeats N            Repeats allowed on any path (default 1; implies --repeat unless 0)
+  --repeat-cost COST         What a Repeat's No edge costs: free (default), soft_no or hard_no
+  --prioritize-soft-no       Minimize hard No edges before all No edges
+  --redeeming-yes N          Redemption per No edge (default 2)
+  --max-depth N              Cap the questions asked for any word
+  --max-consecutive-nos N    Cap runs of consecutive No answers
+  --weights KEY=VALUE,...    Rank by weighted cost (keys: hard_no, soft_no, max, sum,
+                             redemption, question; `default` for the defaults)
+  --naturalness KEY=VALUE,...
+                             Order equally optimal trees, most natural first (keys: positional,
+                             shallow_soft_split, distinct_letter, rare_letter; `none` keeps
+                             the search order; default on)
+
+Other options:
+  -f, --format FORMAT        ascii (default), json, notation, dot, mermaid, script, svg or paths
+  -i, --input FILE           Read input from FILE (`-` for stdin, which a bare `-` argument
+                             also means)
+  -o, --output FILE          Write output to FILE instead of stdout
+  --limit N                  Trees to print with solve (default 5, 0 for all); dot, mermaid,
+                             script, svg and paths render the first tree only
+  --set NAME                 Use a built-in word set (see `sets`) as the word list
+  --words LIST               Word list, comma or space separated (for validate: the words
+                             the tree must cover)
+  --size N                   design: words per subset (required)
+  --require LIST             design: words every subset must contain
+  --top N                    design: subsets to report (default 5)
+  --max-combinations N       design: most subsets to search (default 100000)
+  --replace-with WORD        sensitivity: also try swapping each word for WORD
+  --group                    solve: show one tree per routine, grouping trees that split the
+                             words identically and listing the alternative questions
+  --where QUERY              solve: keep only the optimal trees matching QUERY, e.g.
+                             'root:contains and never:triple and word:leo.hard_nos=0'
+  --optimal                  validate: also check the tree's cost against the optimum
+                             (for the --words list, aliases included, when given)
+  -h, --help                 Print this help
+
+Switches such as --repeat also take =true or =false.
+
+Exit codes: 0 success, 1 no tree found or tree invalid, 2 bad usage or input, 3 I/O error.
+";
+
+/// Largest word list the solver's bitmasks can hold
+const MAX_WORDS: usize = 32;
+
+enum CliError {
+    /// No tree satisfies the constraints, a tree is invalid or a batch entry failed
+    Failed(String),
+    /// Bad arguments or unparseable input
+    Usage(String),
+    Io(String),
+}
+
+impl CliError {
+    const fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Failed(_) => 1,
+            CliError::Usage(_) => 2,
+            CliError::Io(_) => 3,
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            CliError::Failed(message) | CliError::Usage(message) | CliError::Io(message) => message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Ascii,
+    Json,
+    Notation,
+    Dot,
+    Mermaid,
+    Script,
+    Svg,
+    Paths,
+}
+
+impl Format {
+    fn parse(name: &str) -> Result<Self, CliError> {
+        Ok(match name {
+            "ascii" => Format::Ascii,
+            "json" => Format::Json,
+            "notation" => Format::Notation,
+            "dot" => Format::Dot,
+            "mermaid" => Format::Mermaid,
+            "script" => Format::Script,
+            "svg" => Format::Svg,
+            "paths" => Format::Paths,
+            _ => return Err(CliError::Usage(format!("unknown format '{name}'"))),
+        })
+    }
+}
+
+struct Options {
+    positional: Vec<String>,
+    config: SolverConfig,
+    format: Format,
+    input: Option<String>,
+    output: Option<String>,
+    limit: usize,
+    words: Option<Vec<String>>,
+    optimal: bool,
+    size: Option<usize>,
+    require: Vec<String>,
+    top: usize,
+    max_combinations: u64,
+    replace_with: Option<String>,
+    group: bool,
+    query: Option<Query>,
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, CliError> {
+    value.parse().map_err(|_| CliError::Usage(format!("{flag} expects a number, got '{value}'")))
+}
+
+/// A switch is on when given bare, or set by `=true` or `=false`
+fn parse_switch(flag: &str, value: Option<&str>) -> Result<bool, CliError> {
+    match value {
+        None | Some("true") => Ok(true),
+        Some("false") => Ok(false),
+        Some(value) => Err(CliError::Usage(format!("{flag} takes no value, or true or false, got '{value}'"))),
+    }
+}
+
+fn parse_weights(value: &str) -> Result<CostWeights, CliError> {
+    let mut weights = CostWeights::default();
+    if value == "default" {
+        return Ok(weights);
+    }
+    for pair in value.split(',').filter(|pair| !pair.is_empty()) {
+        let Some((key, number)) = pair.split_once('=') else {
+            return Err(CliError::Usage(format!("--weights expects KEY=VALUE pairs, got '{pair}'")));
+        };
+        let number: f64 = parse_number("--weights", number)?;
+        match key.trim() {
+            "hard_no" => weights.hard_no = number,
+            "soft_no" => weights.soft_no = number,
+            "max" => weights.max = number,
+            "sum" => weights.sum = number,
+            "redemption" => weights.redemption = number,
+            "question" => weights.question = number,
+            other => return Err(CliError::Usage(format!("unknown weight '{other}'"))),
+        }
+    }
+    Ok(weights)
+}
+
+fn parse_repeat_cost(value: &str) -> Result<RepeatCost, CliError> {
+    match value {
+        "free" => Ok(RepeatCost::Free),
+        "soft_no" => Ok(RepeatCost::SoftNo),
+        "hard_no" => Ok(RepeatCost::HardNo),
+        _ => Err(CliError::Usage(format!("--repeat-cost expects free, soft_no or hard_no, got '{value}'"))),
+    }
+}
+
+fn parse_naturalness(value: &str) -> Result<Option<NaturalnessWeights>, CliError> {
+    let mut weights = NaturalnessWeights::default();
+    match value {
+        "none" => return Ok(None),
+        "default" => return Ok(Some(weights)),
+        _ => {}
+    }
+    for pair in value.split(',').filter(|pair| !pair.is_empty()) {
+        let Some((key, number)) = pair.split_once('=') else {
+            return Err(CliError::Usage(format!("--naturalness expects KEY=VALUE pairs, got '{pair}'")));
+        };
+        let number: f64 = parse_number("--naturalness", number)?;
+        match key.trim() {
+            "positional" => weights.positional = number,
+            "shallow_soft_split" => weights.shallow_soft_split = number,
+            "distinct_letter" => weights.distinct_letter = number,
+            "rare_letter" => weights.rare_letter = number,
+            other => return Err(CliError::Usage(format!("unknown naturalness weight '{other}'"))),
+        }
+    }
+    Ok(Some(weights))
+}
+
+/// Split a word list on whitespace and commas
+fn split_words(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || c == ',').filter(|w| !w.is_empty()).map(str::to_string).collect()
+}
+
+fn parse_options(args: &[String]) -> Result<Options, CliError> {
+    let mut options = Options {
+        positional: Vec::new(),
+        config: SolverConfig { naturalness: Some(NaturalnessWeights::DEFAULT), ..SolverConfig::default() },
+        format: Format::Ascii,
+        input: None,
+        output: None,
+        limit: 5,
+        words: None,
+        optimal: false,
+        size: None,
+        require: Vec::new(),
+        top: 5,
+        max_combinations: DesignOptions::MAX_COMBINATIONS,
+        replace_with: None,
+        group: false,
+        query: None,
+    };
+
+    let mut args = args.iter();
+    let mut stdin_arg = false;
+    while let Some(arg) = args.next() {
+        // A bare `-` reads the words or trees from stdin, like `--input -`
+        if arg == "-" {
+            stdin_arg = true;
+            continue;
+        }
+        if !arg.starts_with('-') {
+            options.positional.push(arg.clone());
+            continue;
+        }
+        // Accept both `--flag value` and `--flag=value`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        let switch = || parse_switch(flag, inline_value.as_deref());
+        let mut value = || {
+            inline_value.clone().or_else(|| args.next().cloned()).ok_or_else(|| {
+                CliError::Usage(format!("{flag} expects a value"))
+            })
+        };
+        match flag {
+            "--repeat" => options.config.allow_repeat = switch()?,
+            "--max-repeats" => {
+                options.config.max_repeats = parse_number(flag, &value()?)?;
+                options.config.allow_repeat = options.config.max_repeats > 0;
+            }
+            "--repeat-cost" => options.config.repeat_cost = parse_repeat_cost(&value()?)?,
+            "--prioritize-soft-no" => options.config.prioritize_soft_no = switch()?,
+            "--redeeming-yes" => options.config.redeeming_yes = parse_number(flag, &value()?)?,
+            "--max-depth" => options.config.max_depth = Some(parse_number(flag, &value()?)?),
+            "--max-consecutive-nos" => options.config.max_consecutive_nos = Some(parse_number(flag, &value()?)?),
+            "--weights" => options.config.weights = Some(parse_weights(&value()?)?),
+            "--naturalness" => options.config.naturalness = parse_naturalness(&value()?)?,
+            "-f" | "--format" => options.format = Format::parse(&value()?)?,
+            "-i" | "--input" => options.input = Some(value()?),
+            "-o" | "--output" => options.output = Some(value()?),
+            "--limit" => options.limit = parse_number(flag, &value()?)?,
+            "--words" => options.words = Some(split_words(&value()?)),
+            "--set" => {
+                let name = value()?;
+                let set = wordsets::by_name(&name)
+                    .ok_or_else(|| CliError::Usage(format!("unknown word set '{name}' (see `sets`)")))?;
+                options.words = Some(set.to_words());
+            }
+            "--optimal" => options.optimal = switch()?,
+            "--size" => options.size = Some(parse_number(flag, &value()?)?),
+            "--require" => options.require = split_words(&value()?),
+            "--top" => options.top = parse_number(flag, &value()?)?,
+            "--max-combinations" => options.max_combinations = parse_number(flag, &value()?)?,
+            "--replace-with" => options.replace_with = Some(value()?),
+            "--group" => options.group = switch()?,
+            "--where" => {
+                let query = value()?;
+                options.query = Some(
+                    query.parse().map_err(|e| CliError::Usage(format!("--where: {e} in '{query}'")))?,
+                );
+            }
+            _ => return Err(CliError::Usage(format!("unknown option '{flag}'"))),
+        }
+    }
+    if stdin_arg {
+        if options.input.is_some() || !options.positional.is_empty() {
+            let message = "`-` reads stdin, so it cannot be combined with --input or arguments";
+            return Err(CliError::Usage(message.to_string()));
+        }
+        options.input = Some("-".to_string());
+    }
+    Ok(options)
+}
+
+/// Read `path`, or stdin for `None` and `-`
+fn read_input(path: Option<&str>) -> Result<String, CliError> {
+    match path {
+        None | Some("-") => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map_err(|e| CliError::Io(format!("cannot read stdin: {e}")))?;
+            Ok(text)
+        }
+        Some(path) => fs::read_to_string(path).map_err(|e| CliError::Io(format!("cannot read {path}: {e}"))),
+    }
+}
+
+fn write_output(path: Option<&str>, text: &str) -> Result<(), CliError> {
+    match path {
+        None | Some("-") => {
+            print!("{text}");
+            Ok(())
+        }
+        Some(path) => fs::write(path, text).map_err(|e| CliError::Io(format!("cannot write {path}: {e}"))),
+    }
+}
+
+/// Check the size of a word list, and that no spelling (word or alias) appears twice
+fn check_words(words: &[String]) -> Result<(), CliError> {
+    if words.is_empty() {
+        return Err(CliError::Usage("no words given".to_string()));
+    }
+    if words.len() > MAX_WORDS {
+        return Err(CliError::Usage(format!("the solver supports up to {MAX_WORDS} words, got {}", words.len())));
+    }
+    let spellings: Vec<&str> = words.iter().flat_map(|entry| entry.split('=')).filter(|s| !s.is_empty()).collect();
+    for (idx, spelling) in spellings.iter().enumerate() {
+        if spellings[..idx].contains(spelling) {
+            return Err(CliError::Usage(format!("'{spelling}' appears more than once")));
+        }
+    }
+    Ok(())
+}
+
+fn summarize(cost: &Cost) -> String {
+    let word_count = cost.word_count.max(1) as f32;
+    format!(
+        "max hard no {}, max no {}, avg hard no {:.1}, avg no {:.1}, max questions {}, longest No run {}",
+        cost.hard_nos,
+        cost.nos,
+        cost.sum_hard_nos as f32 / word_count,
+        cost.sum_nos as f32 / word_count,
+        cost.max_questions,
+        cost.max_consecutive_nos
+    )
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("tree and cost types serialize to JSON") + "\n"
+}
+
+/// Render a single tree; JSON uses the same structure as the web demo's merged tree
+fn render(tree: &Node, format: Format, repeat_cost: RepeatCost) -> String {
+    match format {
+        Format::Ascii => format_tree(tree) + "\n",
+        Format::Json => to_json(&MergedNode::merge(&[Rc::new(tree.clone())])),
+        Format::Notation => format!("{tree}\n"),
+        Format::Dot => tree.to_dot(),
+        Format::Mermaid => tree.to_mermaid(),
+        Format::Script => format_script(tree, &ScriptTemplates::default()),
+        Format::Svg => render_svg(tree, &SvgOptions::default()),
+        Format::Paths => format_word_paths(&word_paths(tree, repeat_cost)),
+    }
+}
+
+/// The word list from the arguments, --set/--words, --input or stdin
+fn read_words(options: &Options) -> Result<Vec<String>, CliError> {
+    let words = match (&options.words, options.positional.is_empty()) {
+        (Some(_), false) => {
+            return Err(CliError::Usage("give words either as arguments or with --set/--words".to_string()))
+        }
+        (Some(words), true) => words.clone(),
+        (None, true) => split_words(&read_input(options.input.as_deref())?),
+        (None, false) => options.positional.clone(),
+    };
+    check_words(&words)?;
+    Ok(words)
+}
+
+/// Split `scorpio=scorpion=skorpio` into the word and its alternate spellings
+fn split_aliases(entries: Vec<String>) -> (Vec<String>, Vec<Vec<String>>) {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let mut spellings = entry.split('=').filter(|s| !s.is_empty()).map(str::to_string);
+            let word = spellings.next().unwrap_or_default();
+            (word, spellings.collect())
+        })
+        .unzip()
+}
+
+fn solve(options: &Options) -> Result<String, CliError> {
+    let (words, aliases) = split_aliases(read_words(options)?);
+
+    let sol = try_minimal_trees_with_aliases(&words, &aliases, &options.config)
+        .map_err(|e| CliError::Failed(e.to_string()))?;
+    if sol.is_unsolvable() {
+        return Err(CliError::Failed("no tree satisfies these constraints".to_string()));
+    }
+    let sol = match &options.query {
+        Some(query) => sol.filter(query, options.config.repeat_cost),
+        None => sol,
+    };
+    if sol.trees.is_empty() {
+        return Err(CliError::Failed("no optimal tree matches the query".to_string()));
+    }
+    if options.group {
+        return Ok(solve_grouped(&words, &sol, options));
+    }
+    let shown = if options.limit == 0 { sol.trees.len() } else { options.limit.min(sol.trees.len()) };
+
+    let mut out = String::new();
+    match options.format {
+        Format::Ascii => {
+            let _ = writeln!(out, "Best cost = ({}) | {} tree(s)", summarize(&sol.cost), sol.trees.len());
+            for (idx, tree) in sol.trees.iter().take(shown).enumerate() {
+                let _ = writeln!(out, "--- Tree {} ---\n{}", idx + 1, format_tree(tree));
+            }
+            if sol.trees.len() > shown {
+                let _ = writeln!(out, "... {} more optimal tree(s) omitted from display", sol.trees.len() - shown);
+            }
+        }
+        Format::Json => {
+            let trees: Vec<String> = sol.trees.iter().take(shown).map(|tree| tree.to_string()).collect();
+            out = to_json(&serde_json::json!({
+                "words": words,
+                "cost": sol.cost,
+                "tree_count": sol.trees.len(),
+                "trees": trees,
+            }));
+        }
+        Format::Notation => {
+            for tree in sol.trees.iter().take(shown) {
+                let _ = writeln!(out, "{tree}");
+            }
+        }
+        format => out = render(&sol.trees[0], format, options.config.repeat_cost),
+    }
+    Ok(out)
+}
+
+/// `solve --group`: the trees of `sol` by routine, up to `--limit` routines
+fn solve_grouped(words: &[String], sol: &Solution, options: &Options) -> String {
+    let classes = group_by_partition(&sol.trees);
+    let shown = if options.limit == 0 { classes.len() } else { options.limit.min(classes.len()) };
+
+    let mut out = String::new();
+    match options.format {
+        Format::Ascii => {
+            let (summary, count) = (summarize(&sol.cost), sol.trees.len());
+            let _ = writeln!(out, "Best cost = ({summary}) | {count} tree(s) in {} routine(s)", classes.len());
+            for (idx, class) in classes.iter().take(shown).enumerate() {
+                let _ = writeln!(out, "--- Routine {} ({} tree(s)) ---", idx + 1, class.tree_count);
+                out.push_str(&format_tree(&class.tree));
+                let alternatives = format_alternatives(&class.alternatives);
+                if !alternatives.is_empty() {
+                    let _ = write!(out, "Alternative questions:\n{alternatives}");
+                }
+            }
+            if classes.len() > shown {
+                let _ = writeln!(out, "... {} more routine(s) omitted from display", classes.len() - shown);
+            }
+        }
+        Format::Json => {
+            let routines: Vec<_> = classes
+                .iter()
+                .take(shown)
+                .map(|class| {
+                    serde_json::json!({
+                        "tree": class.tree.to_string(),
+                        "tree_count": class.tree_count,
+                        "alternatives": class.alternatives,
+                    })
+                })
+                .collect();
+            out = to_json(&serde_json::json!({
+                "words": words,
+                "cost": sol.cost,
+                "tree_count": sol.trees.len(),
+                "routine_count": classes.len(),
+                "routines": routines,
+            }));
+        }
+        Format::Notation => {
+            for class in classes.iter().take(shown) {
+                let _ = writeln!(out, "{}", class.tree);
+            }
+        }
+        format => out = render(&classes[0].tree, format, options.config.repeat_cost),
+    }
+    out
+}
+
+fn read_tree(options: &Options) -> Result<Node, CliError> {
+    let text = match options.positional.as_slice() {
+        [] => read_input(options.input.as_deref())?,
+        [tree] => tree.clone(),
+        _ => return Err(CliError::Usage("expected a single tree (quote it on the command line)".to_string())),
+    };
+    text.trim().parse().map_err(|e| CliError::Usage(format!("invalid tree: {e}")))
+}
+
+fn diff_command(options: &Options) -> Result<String, CliError> {
+    let text;
+    let trees: Vec<&str> = match options.positional.as_slice() {
+        [] => {
+            text = read_input(options.input.as_deref())?;
+            text.lines().map(str::trim).filter(|line| !line.is_empty()).collect()
+        }
+        trees => trees.iter().map(String::as_str).collect(),
+    };
+    let [before, after] = trees.as_slice() else {
+        return Err(CliError::Usage(format!("diff expects two trees, got {}", trees.len())));
+    };
+    let parse = |tree: &str| tree.trim().parse::<Node>().map_err(|e| CliError::Usage(format!("invalid tree: {e}")));
+    let (before, after) = (parse(before)?, parse(after)?);
+    let changes = diff(&before, &after, options.config.repeat_cost);
+    match options.format {
+        Format::Ascii => Ok(format_diff(&changes, &after)),
+        Format::Json => Ok(to_json(&changes)),
+        _ => Err(CliError::Usage("diff writes ascii or json".to_string())),
+    }
+}
+
+fn render_command(options: &Options) -> Result<String, CliError> {
+    Ok(render(&read_tree(options)?, options.format, options.config.repeat_cost))
+}
+
+fn validate(options: &Options) -> Result<String, CliError> {
+    let tree = read_tree(options)?;
+    // Aliases only constrain the optimum; the tree's leaves carry the words themselves
+    let listed = match &options.words {
+        Some(entries) => {
+            check_words(entries)?;
+            Some(split_aliases(entries.clone()))
+        }
+        None => None,
+    };
+    let listed_words = listed.as_ref().map(|(words, _)| words.as_slice());
+    let mut problems: Vec<String> = validate_tree(&tree, listed_words).iter().map(ToString::to_string).collect();
+    let cost = tree_cost_with_repeats(&tree, options.config.redeeming_yes, options.config.repeat_cost);
+
+    if options.optimal && problems.is_empty() {
+        let (words, aliases) = match listed {
+            Some(listed) => listed,
+            None => {
+                let words: Vec<String> =
+                    word_paths(&tree, options.config.repeat_cost).into_iter().map(|path| path.word).collect();
+                check_words(&words)?;
+                (words, Vec::new())
+            }
+        };
+        match try_minimal_trees_with_aliases(&words, &aliases, &options.config) {
+            Ok(sol) if sol.is_unsolvable() => problems.push("no tree satisfies these constraints".to_string()),
+            Ok(sol) if options.config.compare(&cost, &sol.cost).is_gt() => {
+                problems.push(format!("not optimal: best cost is ({})", summarize(&sol.cost)));
+            }
+            Ok(_) => {}
+            Err(e) => problems.push(e.to_string()),
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(format!("valid: {} word(s), cost ({})\n", cost.word_count, summarize(&cost)))
+    } else {
+        Err(CliError::Failed(problems.join("\n")))
+    }
+}
+
+fn batch(options: &Options) -> Result<String, CliError> {
+    let text = read_input(options.input.as_deref())?;
+    let mut solver = BatchSolver::new(options.config.clone());
+    let mut out = String::new();
+    let mut failures = 0;
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // JSON requests may carry their own config; plain word lists use the flags
+        let result = if line.starts_with('{') {
+            solver.solve_json(idx + 1, line)
+        } else {
+            solver.solve(idx + 1, &BatchRequest { words: split_words(line), ..BatchRequest::default() })
+        };
+        if result.error.is_some() {
+            failures += 1;
+        }
+
+        if options.format == Format::Json {
+            let _ = writeln!(out, "{}", serde_json::to_string(&result).expect("batch results serialize to JSON"));
+        } else {
+            let words = result.words.join(" ");
+            match (&result.cost, &result.error) {
+                (_, Some(error)) => {
+                    let _ = writeln!(out, "{}: {words} | error: {error}", result.line);
+                }
+                (Some(cost), None) => {
+                    let (summary, count, millis) = (summarize(cost), result.tree_count, result.millis);
+                    let _ = writeln!(out, "{}: {words} | {summary} | {count} tree(s) | {millis:.0} ms", result.line);
+                }
+                (None, None) => unreachable!("batch results have a cost or an error"),
+            }
+        }
+    }
+
+    if failures > 0 {
+        // Still write the results of the lists that succeeded
+        write_output(options.output.as_deref(), &out)?;
+        return Err(CliError::Failed(format!("{failures} word list(s) failed")));
+    }
+    Ok(out)
+}
+
+fn design(options: &Options) -> Result<String, CliError> {
+    let pool = read_words(options)?;
+    let size = options.size.ok_or_else(|| CliError::Usage("design needs --size".to_string()))?;
+    let design_options = DesignOptions {
+        size,
+        required: options.require.clone(),
+        top: options.top,
+        max_combinations: options.max_combinations,
+    };
+    let best =
+        design_subsets(&pool, &design_options, &options.config).map_err(|e| CliError::Usage(e.to_string()))?;
+    if best.is_empty() {
+        return Err(CliError::Failed("no subset has a tree satisfying these constraints".to_string()));
+    }
+
+    let mut out = String::new();
+    match options.format {
+        Format::Ascii => {
+            for (idx, candidate) in best.iter().enumerate() {
+                let (words, summary) = (candidate.words.join(" "), summarize(&candidate.solution.cost));
+                let count = candidate.solution.trees.len();
+                let _ = writeln!(out, "#{} {words} | ({summary}) | {count} tree(s)", idx + 1);
+            }
+            let _ = writeln!(out, "--- Best tree ---\n{}", format_tree(&best[0].solution.trees[0]));
+        }
+        Format::Json => {
+            let subsets: Vec<_> = best
+                .iter()
+                .map(|candidate| {
+                    serde_json::json!({
+                        "words": candidate.words,
+                        "cost": candidate.solution.cost,
+                        "tree_count": candidate.solution.trees.len(),
+                        "tree": candidate.solution.trees[0].to_string(),
+                    })
+                })
+                .collect();
+            out = to_json(&subsets);
+        }
+        Format::Notation => {
+            for candidate in &best {
+                let _ = writeln!(out, "{}", candidate.solution.trees[0]);
+            }
+        }
+        format => out = render(&best[0].solution.trees[0], format, options.config.repeat_cost),
+    }
+    Ok(out)
+}
+
+fn sensitivity_command(options: &Options) -> Result<String, CliError> {
+    let words = read_words(options)?;
+    let report = sensitivity(&words, options.replace_with.as_deref(), &options.config)
+        .map_err(|e| CliError::Usage(e.to_string()))?;
+    if options.format == Format::Json {
+        return Ok(to_json(&report));
+    }
+
+    let describe = |cost: &Option<Cost>| match cost {
+        Some(cost) => format!("({})", summarize(cost)),
+        None => "no tree".to_string(),
+    };
+    let mut out = String::new();
+    let _ = writeln!(out, "Full set: {}", describe(&report.cost));
+    let width = report.words.iter().map(|impact| impact.word.len()).max().unwrap_or(0);
+    for impact in &report.words {
+        let _ = writeln!(out, "{:<width$}  without: {}", impact.word, describe(&impact.without));
+        if let Some(alternative) = &report.alternative {
+            let _ = writeln!(out, "{:<width$}  with {alternative}: {}", "", describe(&impact.replaced));
+        }
+    }
+    Ok(out)
+}
+
+fn sets(options: &Options) -> String {
+    if options.format == Format::Json {
+        return to_json(&wordsets::ALL);
+    }
+    let mut out = String::new();
+    for set in wordsets::ALL {
+        let _ = writeln!(out, "{:<16}{:>3} words  {}", set.name, set.words.len(), set.description);
+    }
+    out
+}
+
+fn run(args: &[String]) -> Result<(), CliError> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err(CliError::Usage(format!("missing command\n\n{USAGE}")));
+    };
+    if rest.iter().any(|arg| arg == "-h" || arg == "--help") || command == "-h" || command == "--help" {
+        print!("{USAGE}");
+        return Ok(());
+    }
+
+    let options = parse_options(rest)?;
+    let out = match command.as_str() {
+        "solve" => solve(&options)?,
+        "render" => render_command(&options)?,
+        "validate" => validate(&options)?,
+        "diff" => diff_command(&options)?,
+        "batch" => batch(&options)?,
+        "design" => design(&options)?,
+        "sensitivity" => sensitivity_command(&options)?,
+        "sets" => sets(&options),
+        other => return Err(CliError::Usage(format!("unknown command '{other}'\n\n{USAGE}"))),
+    };
+    write_output(options.output.as_deref(), &out)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e.message());
+            ExitCode::from(e.exit_code())
+        }
+    }
 }
@@ -1,9 +1,10 @@
-use std::collections::HashMap;
-use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::rc::Rc;
+use serde::{Deserialize, Serialize, Serializer};
 use crate::node::{Node, NodeRef, Position};
 
 /// Description of a node's split logic, used for comparing nodes for equality
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 #[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
 pub enum NodeInfo {
     Leaf {
@@ -28,7 +29,7 @@ pub enum NodeInfo {
 
 impl NodeInfo {
     /// Extract node info from a Node, ignoring children
-    fn from_node(node: &Node) -> Self {
+    pub(crate) fn from_node(node: &Node) -> Self {
         match node {
             Node::Leaf(word) => NodeInfo::Leaf { word: word.clone() },
             Node::Repeat { word, .. } => NodeInfo::Repeat { word: word.clone() },
@@ -56,20 +57,62 @@ impl NodeInfo {
     }
 }
 
-/// A single option in a merged node (one possible split + its merged children)
-#[derive(Debug, Clone, Serialize)]
-#[serde(rename_all = "camelCase")]
+/// A single option in a merged node (one possible split + its merged children).
+/// Children are shared between every option whose subtrees merge to the same node.
+#[derive(Debug, Clone)]
 pub struct MergedOption {
     pub info: NodeInfo,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub yes_branch: Option<Box<MergedNode>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub no_branch: Option<Box<MergedNode>>,
+    pub yes_branch: Option<Rc<MergedNode>>,
+    pub no_branch: Option<Rc<MergedNode>>,
+    /// Number of merged trees going through this option
+    pub tree_count: usize,
+    /// Words reached below this option, Yes side first
+    pub words: Vec<String>,
+    /// Letters asked about or required anywhere below this option, in any of its trees, sorted
+    pub letters: String,
+    /// Questions on the longest path from this option down, in any of its trees
+    pub max_depth: u32,
 }
 
-/// A merged node that may have multiple options (when trees differ)
-#[derive(Debug, Clone, Serialize)]
-#[serde(rename_all = "camelCase")]
+impl MergedOption {
+    /// Build an option from its merged children, deriving its statistics from theirs
+    fn new(
+        info: NodeInfo,
+        yes_branch: Option<Rc<MergedNode>>,
+        no_branch: Option<Rc<MergedNode>>,
+        tree_count: usize,
+    ) -> Self {
+        let children = || yes_branch.iter().chain(no_branch.iter());
+
+        // Every option of a merged node covers the same words, so the first one speaks for all
+        let mut words = match &info {
+            NodeInfo::Leaf { word } | NodeInfo::Repeat { word } => vec![word.clone()],
+            _ => Vec::new(),
+        };
+        words.extend(children().filter_map(|child| child.options.first()).flat_map(|option| option.words.clone()));
+
+        let mut letters: BTreeSet<char> =
+            children().flat_map(|child| &child.options).flat_map(|option| option.letters.chars()).collect();
+        if let NodeInfo::PositionalSplit { test_letter, requirement_letter, .. }
+        | NodeInfo::YesSplit { test_letter, requirement_letter, .. } = &info
+        {
+            letters.extend([*test_letter, *requirement_letter]);
+        }
+
+        let max_depth = match info {
+            NodeInfo::Leaf { .. } => 0,
+            _ => 1 + children().map(|child| child.max_depth()).max().unwrap_or(0),
+        };
+        let letters = letters.into_iter().collect();
+        MergedOption { info, yes_branch, no_branch, tree_count, words, letters, max_depth }
+    }
+}
+
+/// A merged node that may have multiple options (when trees differ).
+///
+/// Serializes as `{"nodes": [...]}` with each distinct node listed once, the root first;
+/// options refer to their `yesBranch` and `noBranch` children by index into `nodes`.
+#[derive(Debug, Clone)]
 pub struct MergedNode {
     /// If options.len() == 1, all trees agree (unanimous)
     /// If options.len() > 1, user can choose (choice node)
@@ -77,67 +120,68 @@ pub struct MergedNode {
 }
 
 impl MergedNode {
-    /// Merge multiple trees into a single merged tree structure
+    /// Merge multiple trees into a single merged DAG. Equal subtrees are merged once, and so
+    /// are equal collections of subtrees (counting how many trees reach each), so identical
+    /// merged subtrees are shared wherever they occur.
     pub fn merge(trees: &[NodeRef]) -> Self {
-        if trees.is_empty() {
-            return MergedNode { options: vec![] };
-        }
+        let mut interner = Interner::default();
+        let ids = trees.iter().map(|tree| interner.intern(tree)).collect();
+        let root = interner.merge(ids);
+        drop(interner);
+        Rc::try_unwrap(root).unwrap_or_else(|shared| (*shared).clone())
+    }
 
-        // Group trees by their root node info
-        let mut groups: HashMap<NodeInfo, Vec<NodeRef>> = HashMap::new();
-        for tree in trees {
-            let info = NodeInfo::from_node(tree);
-            groups.entry(info).or_default().push(tree.clone());
+    /// Turn picks made at choice nodes back into one of `trees`, the trees this node merged.
+    ///
+    /// Each pick gives the path of a choice node and the index of the chosen option. Paths are
+    /// followed through the options the resolution itself selects, like the web UI does. Picks
+    /// are applied in order, each narrowing down the trees that remain; a pick that no remaining
+    /// tree agrees with is ignored. Choice nodes without a usable pick take the option of the
+    /// first remaining tree, so the result is always one of the merged trees, and without picks
+    /// it is the first of `trees` (the most natural one, when the solver ranks them).
+    /// Returns `None` for an empty merge.
+    pub fn resolve(&self, trees: &[NodeRef], picks: &[(Vec<Branch>, usize)]) -> Option<Resolution> {
+        if trees.is_empty() || self.options.is_empty() {
+            return None;
         }
+        let latest: HashMap<&[Branch], usize> = picks.iter().rev().map(|(path, idx)| (path.as_slice(), *idx)).collect();
+        let resolver = Resolver { picks: &latest };
+        let roots: Vec<Option<&Node>> = trees.iter().map(|tree| Some(&**tree)).collect();
+        let all = vec![true; trees.len()];
 
-        // For each group, merge the children
-        let mut options: Vec<MergedOption> = groups
-            .into_iter()
-            .map(|(info, group_trees)| {
-                // Extract yes and no branches from all trees in this group
-                let mut yes_branches = Vec::new();
-                let mut no_branches = Vec::new();
-
-                for tree in &group_trees {
-                    match &**tree {
-                        Node::Leaf(_) => {
-                            // Leaves have no children
-                        }
-                        Node::Repeat { no, .. } => {
-                            no_branches.push(no.clone());
-                        }
-                        Node::PositionalSplit { yes, no, .. } => {
-                            yes_branches.push(yes.clone());
-                            no_branches.push(no.clone());
-                        }
-                        Node::YesSplit { yes, .. } => {
-                            yes_branches.push(yes.clone());
-                        }
-                    }
+        let mut remaining = all.clone();
+        let mut applied: Vec<(&[Branch], Vec<bool>)> = Vec::new();
+        let mut ignored = Vec::new();
+        for (path, idx) in picks {
+            let found = resolver.follow(self, &roots, path, &remaining);
+            match found.and_then(|(node, at)| node.options.get(*idx).map(|option| (option, at))) {
+                Some((option, at)) if keeps_any(option, &at, &remaining) => {
+                    applied.push((path, through(option, &at, &all)));
+                    remaining = through(option, &at, &remaining);
                 }
+                _ => ignored.push(path.clone()),
+            }
+        }
 
-                // Recursively merge children
-                let yes_branch = if yes_branches.is_empty() {
-                    None
-                } else {
-                    Some(Box::new(MergedNode::merge(&yes_branches)))
-                };
-
-                let no_branch = if no_branches.is_empty() {
-                    None
-                } else {
-                    Some(Box::new(MergedNode::merge(&no_branches)))
-                };
-
-                MergedOption { info, yes_branch, no_branch }
-            })
-            .collect();
-
-        // Sort options for consistent ordering (first option should be the first tree)
-        // We'll sort by the NodeInfo Debug representation for determinism
-        options.sort_by(|a, b| format!("{:?}", a.info).cmp(&format!("{:?}", b.info)));
+        let mut choices = Vec::new();
+        let tree = resolver.build(self, &roots, &mut Vec::new(), &mut remaining, &mut choices);
+        // An option stays valid if it keeps a tree agreeing with every other applied pick
+        for choice in &mut choices {
+            let mut others = all.clone();
+            for (_, only) in applied.iter().filter(|(path, _)| *path != choice.path.as_slice()) {
+                for (keep, &allowed) in others.iter_mut().zip(only) {
+                    *keep &= allowed;
+                }
+            }
+            let (node, at) = resolver.follow(self, &roots, &choice.path, &all).expect("choice paths exist");
+            choice.valid = (0..node.options.len()).filter(|&idx| keeps_any(&node.options[idx], &at, &others)).collect();
+        }
+        Some(Resolution { tree, choices, ignored })
+    }
 
-        MergedNode { options }
+    /// Questions on the longest path from here down, over all options
+    pub fn max_depth(&self) -> u32 {
+        self.options.iter().map(|option| option.max_depth).max().unwrap_or(0)
     }
 
     /// Check if this is a choice node (multiple options)
@@ -150,3 +194,295 @@ impl MergedNode {
         self.options.len() == 1 && matches!(self.options[0].info, NodeInfo::Leaf { .. })
     }
 }
+
+/// A subtree up to structural equality: its root's info and the ids of its children
+type Shape = (NodeInfo, Option<usize>, Option<usize>);
+
+/// Hash-conses the merged trees' subtrees and the merged nodes built from sets of them
+#[derive(Default)]
+struct Interner {
+    /// Solver trees share their subtrees, so most nodes are found by address
+    by_address: HashMap<*const Node, usize>,
+    by_shape: HashMap<Shape, usize>,
+    shapes: Vec<Shape>,
+    /// Merged nodes by the sorted ids of the subtrees they merge, one per tree reaching them
+    merged: HashMap<Vec<usize>, Rc<MergedNode>>,
+}
+
+impl Interner {
+    fn intern(&mut self, node: &NodeRef) -> usize {
+        if let Some(&id) = self.by_address.get(&Rc::as_ptr(node)) {
+            return id;
+        }
+        let (yes, no) = match &**node {
+            Node::Leaf(_) => (None, None),
+            Node::Repeat { no, .. } => (None, Some(self.intern(no))),
+            Node::PositionalSplit { yes, no, .. } => (Some(self.intern(yes)), Some(self.intern(no))),
+            Node::YesSplit { yes, .. } => (Some(self.intern(yes)), None),
+        };
+        let shape = (NodeInfo::from_node(node), yes, no);
+        let id = match self.by_shape.get(&shape) {
+            Some(&id) => id,
+            None => {
+                self.shapes.push(shape.clone());
+                self.by_shape.insert(shape, self.shapes.len() - 1);
+                self.shapes.len() - 1
+            }
+        };
+        self.by_address.insert(Rc::as_ptr(node), id);
+        id
+    }
+
+    /// The merged node of the subtrees `ids`, built once per distinct collection. Ids repeat
+    /// when several trees share a subtree, so option counts stay counts of trees.
+    fn merge(&mut self, mut ids: Vec<usize>) -> Rc<MergedNode> {
+        ids.sort_unstable();
+        if let Some(node) = self.merged.get(&ids) {
+            return node.clone();
+        }
+
+        // Group subtrees by their root node info; the map keeps options in structural order
+        let mut groups: BTreeMap<NodeInfo, (Vec<usize>, Vec<usize>, usize)> = BTreeMap::new();
+        for &id in &ids {
+            let (info, yes, no) = &self.shapes[id];
+            let (yes_ids, no_ids, count) = groups.entry(info.clone()).or_default();
+            yes_ids.extend(yes);
+            no_ids.extend(no);
+            *count += 1;
+        }
+
+        let mut options = Vec::with_capacity(groups.len());
+        for (info, (yes_ids, no_ids, count)) in groups {
+            let yes_branch = (!yes_ids.is_empty()).then(|| self.merge(yes_ids));
+            let no_branch = (!no_ids.is_empty()).then(|| self.merge(no_ids));
+            options.push(MergedOption::new(info, yes_branch, no_branch, count));
+        }
+        let node = Rc::new(MergedNode { options });
+        self.merged.insert(ids, node.clone());
+        node
+    }
+}
+
+impl Serialize for MergedNode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut table = NodeTable::default();
+        table.add(self);
+        SerializedDag { nodes: table.nodes }.serialize(serializer)
+    }
+}
+
+#[derive(Serialize)]
+struct SerializedDag<'a> {
+    nodes: Vec<SerializedNode<'a>>,
+}
+
+#[derive(Serialize)]
+struct SerializedNode<'a> {
+    options: Vec<SerializedOption<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerializedOption<'a> {
+    info: &'a NodeInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    yes_branch: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    no_branch: Option<usize>,
+    tree_count: usize,
+    words: &'a [String],
+    letters: &'a str,
+    max_depth: u32,
+}
+
+/// Numbers the distinct nodes of a merged DAG in pre-order
+#[derive(Default)]
+struct NodeTable<'a> {
+    ids: HashMap<*const MergedNode, usize>,
+    nodes: Vec<SerializedNode<'a>>,
+}
+
+impl<'a> NodeTable<'a> {
+    fn add(&mut self, node: &'a MergedNode) -> usize {
+        if let Some(&id) = self.ids.get(&(node as *const MergedNode)) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node, id);
+        self.nodes.push(SerializedNode { options: Vec::new() });
+        let options = node
+            .options
+            .iter()
+            .map(|option| SerializedOption {
+                info: &option.info,
+                yes_branch: option.yes_branch.as_deref().map(|yes| self.add(yes)),
+                no_branch: option.no_branch.as_deref().map(|no| self.add(no)),
+                tree_count: option.tree_count,
+                words: &option.words,
+                letters: &option.letters,
+                max_depth: option.max_depth,
+            })
+            .collect();
+        self.nodes[id].options = options;
+        id
+    }
+}
+
+/// Step from a node to one of its children, the components of a choice node's path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Branch {
+    Yes,
+    No,
+}
+
+/// A choice node of a resolved tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Choice {
+    pub path: Vec<Branch>,
+    /// Option used in the resolved tree
+    pub selected: usize,
+    /// Options that lead to one of the merged trees given the other applied picks
+    pub valid: Vec<usize>,
+}
+
+/// Result of `MergedNode::resolve`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    pub tree: Node,
+    /// Choice nodes of the resolved tree, in pre-order (Yes before No)
+    pub choices: Vec<Choice>,
+    /// Paths of the picks that were ignored
+    pub ignored: Vec<Vec<Branch>>,
+}
+
+/// Where each merged tree stands at a merged node: its node there, `None` once the tree has
+/// left the options selected on the way
+type Cursors<'t> = Vec<Option<&'t Node>>;
+
+/// `remaining` narrowed down to the trees going through `option`
+fn through(option: &MergedOption, at: &[Option<&Node>], remaining: &[bool]) -> Vec<bool> {
+    at.iter()
+        .zip(remaining)
+        .map(|(node, &keep)| keep && node.is_some_and(|node| NodeInfo::from_node(node) == option.info))
+        .collect()
+}
+
+fn keeps_any(option: &MergedOption, at: &[Option<&Node>], remaining: &[bool]) -> bool {
+    through(option, at, remaining).contains(&true)
+}
+
+/// The child of `node` on `branch`, if it has one (a Repeat's Yes answer is not a node)
+pub(crate) fn child(node: &Node, branch: Branch) -> Option<&Node> {
+    match (node, branch) {
+        (Node::PositionalSplit { yes, .. } | Node::YesSplit { yes, .. }, Branch::Yes) => Some(yes),
+        (Node::PositionalSplit { no, .. } | Node::Repeat { no, .. }, Branch::No) => Some(no),
+        _ => None,
+    }
+}
+
+/// The cursors one step below `option`
+fn descend<'t>(option: &MergedOption, at: &[Option<&'t Node>], branch: Branch) -> Cursors<'t> {
+    at.iter()
+        .map(|node| {
+            (*node).filter(|node| NodeInfo::from_node(node) == option.info).and_then(|node| child(node, branch))
+        })
+        .collect()
+}
+
+struct Resolver<'a> {
+    picks: &'a HashMap<&'a [Branch], usize>,
+}
+
+impl Resolver<'_> {
+    /// The picked option of `node` if it keeps a tree, else the option of the first remaining tree
+    fn choose<'n>(
+        &self,
+        node: &'n MergedNode,
+        at: &[Option<&Node>],
+        path: &[Branch],
+        remaining: &[bool],
+    ) -> Option<(usize, &'n MergedOption)> {
+        self.picks
+            .get(path)
+            .and_then(|&idx| node.options.get(idx).map(|option| (idx, option)))
+            .filter(|(_, option)| keeps_any(option, at, remaining))
+            .or_else(|| {
+                let first = at.iter().zip(remaining).find_map(|(node, &keep)| node.filter(|_| keep))?;
+                let info = NodeInfo::from_node(first);
+                node.options.iter().enumerate().find(|(_, option)| option.info == info)
+            })
+    }
+
+    /// The node at `path` and the cursors there, going through the options `choose` selects
+    fn follow<'n, 't>(
+        &self,
+        root: &'n MergedNode,
+        roots: &[Option<&'t Node>],
+        path: &[Branch],
+        remaining: &[bool],
+    ) -> Option<(&'n MergedNode, Cursors<'t>)> {
+        let mut remaining = remaining.to_vec();
+        let mut at = roots.to_vec();
+        let mut node = root;
+        for (depth, &branch) in path.iter().enumerate() {
+            let (_, option) = self.choose(node, &at, &path[..depth], &remaining)?;
+            remaining = through(option, &at, &remaining);
+            at = descend(option, &at, branch);
+            node = match branch {
+                Branch::Yes => option.yes_branch.as_deref()?,
+                Branch::No => option.no_branch.as_deref()?,
+            };
+        }
+        Some((node, at))
+    }
+
+    fn build(
+        &self,
+        node: &MergedNode,
+        at: &[Option<&Node>],
+        path: &mut Vec<Branch>,
+        remaining: &mut Vec<bool>,
+        choices: &mut Vec<Choice>,
+    ) -> Node {
+        let (selected, option) = self.choose(node, at, path, remaining).expect("remaining trees go through this node");
+        *remaining = through(option, at, remaining);
+        if node.is_choice() {
+            choices.push(Choice { path: path.clone(), selected, valid: Vec::new() });
+        }
+        let mut child = |branch: Branch, merged: &Option<Rc<MergedNode>>, remaining: &mut Vec<bool>| {
+            path.push(branch);
+            let merged = merged.as_deref().expect("split options have both branches");
+            let node = self.build(merged, &descend(option, at, branch), path, remaining, choices);
+            path.pop();
+            Rc::new(node)
+        };
+        match &option.info {
+            NodeInfo::Leaf { word } => Node::Leaf(word.clone()),
+            NodeInfo::Repeat { word } => {
+                Node::Repeat { word: word.clone(), no: child(Branch::No, &option.no_branch, remaining) }
+            }
+            NodeInfo::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position } => {
+                let yes = child(Branch::Yes, &option.yes_branch, remaining);
+                let no = child(Branch::No, &option.no_branch, remaining);
+                Node::PositionalSplit {
+                    test_letter: *test_letter,
+                    test_position: *test_position,
+                    requirement_letter: *requirement_letter,
+                    requirement_position: *requirement_position,
+                    yes,
+                    no,
+                }
+            }
+            NodeInfo::YesSplit { test_letter, test_position, requirement_letter, requirement_position } => {
+                Node::YesSplit {
+                    test_letter: *test_letter,
+                    test_position: *test_position,
+                    requirement_letter: *requirement_letter,
+                    requirement_position: *requirement_position,
+                    yes: child(Branch::Yes, &option.yes_branch, remaining),
+                }
+            }
+        }
+    }
+}
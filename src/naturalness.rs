@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+use crate::node::{is_hard_split, Node, Position, Solution};
+
+/// How much each trait of a tree counts against its naturalness (see `naturalness`).
+/// Deserializing fills missing fields from `Default`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NaturalnessWeights {
+    /// Per question or requirement about a position rather than Contains.
+    pub positional: f64,
+    /// Per soft split, divided by one plus its depth: soft splits near the root cost the most.
+    pub shallow_soft_split: f64,
+    /// Per distinct letter asked about or required anywhere in the tree.
+    pub distinct_letter: f64,
+    /// Per letter asked about or required, times its rarity (0 for E, near 1 for Z).
+    pub rare_letter: f64,
+}
+
+impl NaturalnessWeights {
+    pub const DEFAULT: NaturalnessWeights =
+        NaturalnessWeights { positional: 1.0, shallow_soft_split: 1.0, distinct_letter: 0.5, rare_letter: 1.0 };
+}
+
+impl Default for NaturalnessWeights {
+    fn default() -> Self {
+        NaturalnessWeights::DEFAULT
+    }
+}
+
+/// Frequency of `a` to `z` in English text, in percent
+const LETTER_FREQUENCY: [f64; 26] = [
+    8.2, 1.5, 2.8, 4.3, 12.7, 2.2, 2.0, 6.1, 7.0, 0.15, 0.77, 4.0, 2.4, 6.7, 7.5, 1.9, 0.095, 6.0, 6.3, 9.1, 2.8, 0.98,
+    2.4, 0.15, 2.0, 0.074,
+];
+
+/// 0 for the most common letter, approaching 1 for the rarest; letters outside `a`-`z` count as rare
+fn rarity(letter: char) -> f64 {
+    match letter.to_ascii_lowercase() {
+        c @ 'a'..='z' => 1.0 - LETTER_FREQUENCY[c as usize - 'a' as usize] / LETTER_FREQUENCY[4],
+        _ => 1.0,
+    }
+}
+
+/// Penalty of one question at `depth`, adding its letters to `letters`; 0 for leaves and Repeats
+fn question_penalty(node: &Node, depth: u32, weights: &NaturalnessWeights, letters: &mut Vec<char>) -> f64 {
+    let (Node::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position, .. }
+    | Node::YesSplit { test_letter, test_position, requirement_letter, requirement_position, .. }) = node
+    else {
+        return 0.0;
+    };
+    let hard = is_hard_split(*test_letter, *test_position, *requirement_letter, *requirement_position);
+    let mut asked = vec![(*test_letter, *test_position)];
+    if !hard {
+        asked.push((*requirement_letter, *requirement_position));
+    }
+
+    let mut penalty = if hard { 0.0 } else { weights.shallow_soft_split / f64::from(depth + 1) };
+    for (letter, position) in asked {
+        if position != Position::Contains {
+            penalty += weights.positional;
+        }
+        penalty += weights.rare_letter * rarity(letter);
+        if !letters.contains(&letter) {
+            letters.push(letter);
+        }
+    }
+    penalty
+}
+
+/// Penalty for how unnatural a tree is to perform, lower is better. Used to order trees of
+/// equal cost; it never trades against `Cost`.
+pub fn naturalness(tree: &Node, weights: &NaturalnessWeights) -> f64 {
+    fn walk(node: &Node, depth: u32, weights: &NaturalnessWeights, letters: &mut Vec<char>) -> f64 {
+        let own = question_penalty(node, depth, weights, letters);
+        let children = match node {
+            Node::Leaf(_) => 0.0,
+            Node::Repeat { no, .. } => walk(no, depth + 1, weights, letters),
+            Node::PositionalSplit { yes, no, .. } => {
+                walk(yes, depth + 1, weights, letters) + walk(no, depth + 1, weights, letters)
+            }
+            Node::YesSplit { yes, .. } => walk(yes, depth + 1, weights, letters),
+        };
+        own + children
+    }
+
+    let mut letters = Vec::new();
+    let penalty = walk(tree, 0, weights, &mut letters);
+    penalty + weights.distinct_letter * letters.len() as f64
+}
+
+impl Solution {
+    /// Order the trees by `score`, lowest first; ties keep their order.
+    pub fn rank_by(&mut self, mut score: impl FnMut(&Node) -> f64) {
+        let mut scored: Vec<(f64, _)> = self.trees.drain(..).map(|tree| (score(&tree), tree)).collect();
+        scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.trees = scored.into_iter().map(|(_, tree)| tree).collect();
+    }
+
+    /// Order the trees by `naturalness`, most natural first.
+    pub fn rank_by_naturalness(&mut self, weights: &NaturalnessWeights) {
+        self.rank_by(|tree| naturalness(tree, weights));
+    }
+}
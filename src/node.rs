@@ -3,7 +3,7 @@ use std::rc::Rc;
 use crate::cost::Cost;
 
 /// Represents the position/type of a split
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize)]
 pub enum Position {
     Contains,
     First,
@@ -149,6 +149,10 @@ impl Solution {
                 redeemed_sum_hard_nos: i32::MAX,
                 sum_nos: u32::MAX,
                 redeemed_sum_nos: i32::MAX,
+                max_consecutive_nos: u32::MAX,
+                leading_nos: u32::MAX,
+                max_questions: u32::MAX,
+                sum_questions: u32::MAX,
                 word_count,
             },
             trees: Vec::new(),
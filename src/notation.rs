@@ -0,0 +1,296 @@
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::node::{Node, Position};
+
+impl Position {
+    /// Short code used by the compact tree notation
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Position::Contains => "C",
+            Position::First => "F",
+            Position::Second => "S",
+            Position::Third => "T",
+            Position::ThirdToLast => "TTL",
+            Position::SecondToLast => "STL",
+            Position::Last => "L",
+            Position::Double => "D",
+            Position::Triple => "TR",
+        }
+    }
+
+    /// Inverse of `code`
+    pub fn from_code(code: &str) -> Option<Position> {
+        Some(match code {
+            "C" => Position::Contains,
+            "F" => Position::First,
+            "S" => Position::Second,
+            "T" => Position::Third,
+            "TTL" => Position::ThirdToLast,
+            "STL" => Position::SecondToLast,
+            "L" => Position::Last,
+            "D" => Position::Double,
+            "TR" => Position::Triple,
+            _ => return None,
+        })
+    }
+}
+
+/// Characters that end a bare word
+const fn is_reserved(c: char) -> bool {
+    matches!(c, '(' | ')' | ',' | '/' | ':' | '"' | '\\') || c.is_whitespace()
+}
+
+/// Whether `word` must be quoted to parse back as a single leaf
+fn needs_quotes(word: &str) -> bool {
+    word.is_empty() || word.starts_with(['*', '+']) || word.chars().any(is_reserved)
+}
+
+fn write_word(f: &mut fmt::Formatter<'_>, word: &str) -> fmt::Result {
+    if !needs_quotes(word) {
+        return f.write_str(word);
+    }
+    f.write_str("\"")?;
+    for c in word.chars() {
+        if matches!(c, '"' | '\\') {
+            f.write_str("\\")?;
+        }
+        write!(f, "{c}")?;
+    }
+    f.write_str("\"")
+}
+
+fn write_test(
+    f: &mut fmt::Formatter<'_>,
+    test_letter: char,
+    test_position: Position,
+    requirement_letter: char,
+    requirement_position: Position,
+) -> fmt::Result {
+    write!(f, "{}:{test_letter}", test_position.code())?;
+    if (test_letter, test_position) != (requirement_letter, requirement_position) {
+        write!(f, "/{}:{requirement_letter}", requirement_position.code())?;
+    }
+    Ok(())
+}
+
+/// Compact notation, e.g. `C:r(F:a/L:a(aries, leo), *virgo(libra))`:
+///
+/// - a leaf is its word, quoted (`"..."` with `\` escapes) if it contains punctuation or spaces
+/// - a split is `CODE:letter(yes, no)`, with `/CODE:letter` after the test for a soft requirement
+/// - a `YesSplit` is `+CODE:letter(yes)`, with the same optional requirement
+/// - a Repeat is `*word(no)`
+///
+/// Position codes are listed by `Position::code`. `FromStr` parses it back exactly.
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Node::Leaf(word) => write_word(f, word),
+            Node::Repeat { word, no } => {
+                f.write_str("*")?;
+                write_word(f, word)?;
+                write!(f, "({no})")
+            }
+            Node::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position, yes, no } => {
+                write_test(f, *test_letter, *test_position, *requirement_letter, *requirement_position)?;
+                write!(f, "({yes}, {no})")
+            }
+            Node::YesSplit { test_letter, test_position, requirement_letter, requirement_position, yes } => {
+                f.write_str("+")?;
+                write_test(f, *test_letter, *test_position, *requirement_letter, *requirement_position)?;
+                write!(f, "({yes})")
+            }
+        }
+    }
+}
+
+/// Returned when a string is not valid tree notation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNodeError {
+    /// Byte offset where parsing failed.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseNodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for ParseNodeError {}
+
+struct Parser<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ParseNodeError> {
+        Err(ParseNodeError { offset: self.offset, message: message.into() })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseNodeError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(c) => self.error(format!("expected '{expected}', found '{c}'")),
+            None => self.error(format!("expected '{expected}', found end of input")),
+        }
+    }
+
+    /// A bare run of non-reserved characters (possibly empty)
+    fn bare(&mut self) -> &str {
+        let start = self.offset;
+        while self.peek().is_some_and(|c| !is_reserved(c)) {
+            self.bump();
+        }
+        &self.text[start..self.offset]
+    }
+
+    fn quoted(&mut self) -> Result<String, ParseNodeError> {
+        self.bump();
+        let mut word = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(word),
+                Some('\\') => match self.bump() {
+                    Some(c) => word.push(c),
+                    None => return self.error("unterminated escape"),
+                },
+                Some(c) => word.push(c),
+                None => return self.error("unterminated quoted word"),
+            }
+        }
+    }
+
+    fn word(&mut self) -> Result<String, ParseNodeError> {
+        self.skip_whitespace();
+        if self.peek() == Some('"') {
+            return self.quoted();
+        }
+        let word = self.bare().to_string();
+        if word.is_empty() {
+            return self.error("expected a word");
+        }
+        Ok(word)
+    }
+
+    /// `CODE:letter`, starting at the code
+    fn test(&mut self) -> Result<(Position, char), ParseNodeError> {
+        self.skip_whitespace();
+        let start = self.offset;
+        let code = self.bare();
+        let Some(position) = Position::from_code(code) else {
+            let message = format!("unknown position code '{code}'");
+            return Err(ParseNodeError { offset: start, message });
+        };
+        self.expect(':')?;
+        self.letter().map(|letter| (position, letter))
+    }
+
+    /// The single letter right after a ':'
+    fn letter(&mut self) -> Result<char, ParseNodeError> {
+        match self.bump() {
+            Some(letter) => Ok(letter),
+            None => self.error("expected a letter"),
+        }
+    }
+
+    /// Optional `/CODE:letter` requirement, defaulting to the test itself (a hard split)
+    fn requirement(&mut self, test: (Position, char)) -> Result<(Position, char), ParseNodeError> {
+        self.skip_whitespace();
+        if self.peek() != Some('/') {
+            return Ok(test);
+        }
+        self.bump();
+        self.test()
+    }
+
+    fn node(&mut self) -> Result<Node, ParseNodeError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('*') => {
+                self.bump();
+                let word = self.word()?;
+                self.expect('(')?;
+                let no = Rc::new(self.node()?);
+                self.expect(')')?;
+                Ok(Node::Repeat { word, no })
+            }
+            Some('+') => {
+                self.bump();
+                let (test_position, test_letter) = self.test()?;
+                let (requirement_position, requirement_letter) = self.requirement((test_position, test_letter))?;
+                self.expect('(')?;
+                let yes = Rc::new(self.node()?);
+                self.expect(')')?;
+                Ok(Node::YesSplit { test_letter, test_position, requirement_letter, requirement_position, yes })
+            }
+            Some('"') => self.quoted().map(Node::Leaf),
+            Some(_) => {
+                let start = self.offset;
+                let token = self.bare().to_string();
+                if self.peek() != Some(':') {
+                    if token.is_empty() {
+                        return self.error("expected a tree");
+                    }
+                    return Ok(Node::Leaf(token));
+                }
+                // Not a leaf after all: re-read the token as a position code
+                self.offset = start;
+                let (test_position, test_letter) = self.test()?;
+                let (requirement_position, requirement_letter) = self.requirement((test_position, test_letter))?;
+                self.expect('(')?;
+                let yes = Rc::new(self.node()?);
+                self.expect(',')?;
+                let no = Rc::new(self.node()?);
+                self.expect(')')?;
+                Ok(Node::PositionalSplit {
+                    test_letter,
+                    test_position,
+                    requirement_letter,
+                    requirement_position,
+                    yes,
+                    no,
+                })
+            }
+            None => self.error("expected a tree, found end of input"),
+        }
+    }
+}
+
+impl FromStr for Node {
+    type Err = ParseNodeError;
+
+    /// Parse the compact notation written by `Display`. Whitespace between tokens is ignored.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { text, offset: 0 };
+        let node = parser.node()?;
+        parser.skip_whitespace();
+        if parser.peek().is_some() {
+            return parser.error("unexpected trailing input");
+        }
+        Ok(node)
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::format::{format_path, format_question};
+use crate::merged::{child, Branch, NodeInfo};
+use crate::node::{Node, NodeRef, Solution};
+
+/// Optimal trees that split the words the same way at every node, differing only in the
+/// letters and positions their questions use: one routine, up to the wording of its questions.
+#[derive(Debug, Clone)]
+pub struct TreeClass {
+    /// The class's first tree in the order given
+    pub tree: NodeRef,
+    /// Trees in the class
+    pub tree_count: usize,
+    /// The questions the class's trees ask at each split of `tree`, in pre-order (Yes before No)
+    pub alternatives: Vec<Alternatives>,
+}
+
+/// Questions that split the words at one node identically
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Alternatives {
+    pub path: Vec<Branch>,
+    /// Sorted; the representative's own question is among them
+    pub questions: Vec<NodeInfo>,
+}
+
+/// The partitions a subtree induces, with children by id: equal ids mean the same word
+/// partitions at every node, whatever the questions
+#[derive(PartialEq, Eq, Hash)]
+enum Partition {
+    Leaf(String),
+    Repeat(String, usize),
+    Split(usize, usize),
+    YesSplit(usize),
+}
+
+#[derive(Default)]
+struct Partitions {
+    by_address: HashMap<*const Node, usize>,
+    ids: HashMap<Partition, usize>,
+}
+
+impl Partitions {
+    fn id(&mut self, node: &Node) -> usize {
+        if let Some(&id) = self.by_address.get(&(node as *const Node)) {
+            return id;
+        }
+        let partition = match node {
+            Node::Leaf(word) => Partition::Leaf(word.clone()),
+            Node::Repeat { word, no } => Partition::Repeat(word.clone(), self.id(no)),
+            Node::PositionalSplit { yes, no, .. } => Partition::Split(self.id(yes), self.id(no)),
+            Node::YesSplit { yes, .. } => Partition::YesSplit(self.id(yes)),
+        };
+        let next = self.ids.len();
+        let id = *self.ids.entry(partition).or_insert(next);
+        self.by_address.insert(node, id);
+        id
+    }
+}
+
+/// Group `trees` into classes by the sequence of word partitions they induce, in the order of
+/// each class's first tree.
+pub fn group_by_partition(trees: &[NodeRef]) -> Vec<TreeClass> {
+    let mut partitions = Partitions::default();
+    let mut class_of: HashMap<usize, usize> = HashMap::new();
+    let mut members: Vec<Vec<&NodeRef>> = Vec::new();
+    for tree in trees {
+        let idx = *class_of.entry(partitions.id(tree)).or_insert(members.len());
+        if idx == members.len() {
+            members.push(Vec::new());
+        }
+        members[idx].push(tree);
+    }
+
+    members
+        .into_iter()
+        .map(|members| {
+            let nodes: Vec<&Node> = members.iter().map(|tree| &***tree).collect();
+            let mut alternatives = Vec::new();
+            collect_alternatives(&nodes, &mut Vec::new(), &mut alternatives);
+            TreeClass { tree: members[0].clone(), tree_count: members.len(), alternatives }
+        })
+        .collect()
+}
+
+/// Alternatives at every split, walking trees of one class in step
+fn collect_alternatives(nodes: &[&Node], path: &mut Vec<Branch>, out: &mut Vec<Alternatives>) {
+    if matches!(nodes[0], Node::PositionalSplit { .. } | Node::YesSplit { .. }) {
+        let mut questions: Vec<NodeInfo> = nodes.iter().map(|node| NodeInfo::from_node(node)).collect();
+        questions.sort();
+        questions.dedup();
+        out.push(Alternatives { path: path.clone(), questions });
+    }
+    for branch in [Branch::Yes, Branch::No] {
+        let children: Vec<&Node> = nodes.iter().filter_map(|node| child(node, branch)).collect();
+        if !children.is_empty() {
+            path.push(branch);
+            collect_alternatives(&children, path, out);
+            path.pop();
+        }
+    }
+}
+
+/// List the splits that have more than one question, one per line, e.g.
+/// `Yes → No: Contains 'R'? | Contains 'E'?`
+pub fn format_alternatives(alternatives: &[Alternatives]) -> String {
+    let mut out = String::new();
+    for alternative in alternatives.iter().filter(|alternative| alternative.questions.len() > 1) {
+        let questions: Vec<String> = alternative.questions.iter().map(format_question).collect();
+        out.push_str(&format!("{}: {}\n", format_path(&alternative.path), questions.join(" | ")));
+    }
+    out
+}
+
+impl Solution {
+    /// This solution with one tree per `group_by_partition` class, its first
+    pub fn one_per_partition(&self) -> Solution {
+        let trees = group_by_partition(&self.trees).into_iter().map(|class| class.tree).collect();
+        Solution { cost: self.cost, trees }
+    }
+}
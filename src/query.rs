@@ -0,0 +1,327 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::config::RepeatCost;
+use crate::merged::{child, Branch};
+use crate::node::{is_hard_split, Node, Position, Solution};
+use crate::report::{word_paths, WordPath};
+
+/// What a single node of a tree can be asked to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Leaf,
+    Repeat,
+    /// A split with a No branch, hard or soft
+    Split,
+    YesSplit,
+    /// A split or `YesSplit` whose requirement is its test
+    Hard,
+    /// A split whose requirement differs from its test
+    Soft,
+    /// A split or `YesSplit` testing or requiring this position
+    Position(Position),
+    /// A split or `YesSplit` testing or requiring this letter
+    Letter(char),
+}
+
+impl NodeKind {
+    pub fn matches(&self, node: &Node) -> bool {
+        let question = match node {
+            Node::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position, .. }
+            | Node::YesSplit { test_letter, test_position, requirement_letter, requirement_position, .. } => {
+                Some((*test_letter, *test_position, *requirement_letter, *requirement_position))
+            }
+            _ => None,
+        };
+        match (self, question) {
+            (NodeKind::Leaf, _) => matches!(node, Node::Leaf(_)),
+            (NodeKind::Repeat, _) => matches!(node, Node::Repeat { .. }),
+            (NodeKind::Split, _) => matches!(node, Node::PositionalSplit { .. }),
+            (NodeKind::YesSplit, _) => matches!(node, Node::YesSplit { .. }),
+            (_, None) => false,
+            (NodeKind::Hard, Some((tl, tp, rl, rp))) => is_hard_split(tl, tp, rl, rp),
+            (NodeKind::Soft, Some((tl, tp, rl, rp))) => {
+                matches!(node, Node::PositionalSplit { .. }) && !is_hard_split(tl, tp, rl, rp)
+            }
+            (NodeKind::Position(position), Some((_, tp, _, rp))) => tp == *position || rp == *position,
+            (NodeKind::Letter(letter), Some((tl, _, rl, _))) => tl == *letter || rl == *letter,
+        }
+    }
+}
+
+/// A number reported per word by `word_paths`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordMetric {
+    Questions,
+    Nos,
+    HardNos,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    const fn holds(self, left: u32, right: u32) -> bool {
+        match self {
+            Comparison::Eq => left == right,
+            Comparison::Ne => left != right,
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+        }
+    }
+}
+
+/// A predicate over trees, built directly or parsed from a query string.
+///
+/// Query strings combine terms with `and`, `or`, `not` and parentheses (`and` binds tighter
+/// than `or`). Terms:
+///
+/// - `root:KIND`, `any:KIND`, `never:KIND`: the root, some node, or no node is of a kind
+/// - `at:PATH:KIND`: the node reached by a `.`-separated path of `yes` and `no` is of a kind
+/// - `word:WORD.METRIC OP N`, without spaces: the word's `questions`, `nos` or `hard_nos`
+///   compare with `N` by `=`, `!=`, `<`, `<=`, `>` or `>=`
+///
+/// Kinds are `leaf`, `repeat`, `split`, `yes-split`, `hard`, `soft`, a position (`contains`,
+/// `first`, `second`, `third`, `third-to-last`, `second-to-last`, `last`, `double`, `triple`)
+/// or `letter:X`. For example: `root:contains and never:triple and word:leo.hard_nos=0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    /// The root is of this kind
+    Root(NodeKind),
+    /// Some node is of this kind
+    Any(NodeKind),
+    /// The node at this path exists and is of this kind
+    At(Vec<Branch>, NodeKind),
+    /// The word (ignoring ASCII case) is in the tree and its path's metric compares as given with `value`
+    Word { word: String, metric: WordMetric, comparison: Comparison, value: u32 },
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+impl Query {
+    /// Whether `tree` matches, counting the No edge of each Repeat as `repeat_cost` says
+    pub fn matches(&self, tree: &Node, repeat_cost: RepeatCost) -> bool {
+        self.matches_with(tree, repeat_cost, &mut None)
+    }
+
+    /// `matches`, computing the word paths at most once per tree
+    fn matches_with(&self, tree: &Node, repeat_cost: RepeatCost, paths: &mut Option<Vec<WordPath>>) -> bool {
+        match self {
+            Query::Root(kind) => kind.matches(tree),
+            Query::Any(kind) => any_node(tree, kind),
+            Query::At(path, kind) => {
+                let node = path.iter().try_fold(tree, |node, &branch| child(node, branch));
+                node.is_some_and(|node| kind.matches(node))
+            }
+            Query::Word { word, metric, comparison, value } => {
+                let paths = paths.get_or_insert_with(|| word_paths(tree, repeat_cost));
+                paths.iter().find(|path| path.word.eq_ignore_ascii_case(word)).is_some_and(|path| {
+                    let actual = match metric {
+                        WordMetric::Questions => path.questions(),
+                        WordMetric::Nos => path.nos,
+                        WordMetric::HardNos => path.hard_nos,
+                    };
+                    comparison.holds(actual, *value)
+                })
+            }
+            Query::Not(query) => !query.matches_with(tree, repeat_cost, paths),
+            Query::And(queries) => queries.iter().all(|query| query.matches_with(tree, repeat_cost, paths)),
+            Query::Or(queries) => queries.iter().any(|query| query.matches_with(tree, repeat_cost, paths)),
+        }
+    }
+}
+
+fn any_node(node: &Node, kind: &NodeKind) -> bool {
+    kind.matches(node)
+        || [Branch::Yes, Branch::No].into_iter().filter_map(|branch| child(node, branch)).any(|c| any_node(c, kind))
+}
+
+impl Solution {
+    /// The trees matching `query`, with this solution's cost; no re-solving involved.
+    /// `repeat_cost` should be the one the solution was found with.
+    pub fn filter(&self, query: &Query, repeat_cost: RepeatCost) -> Solution {
+        let trees = self.trees.iter().filter(|tree| query.matches(tree, repeat_cost)).cloned().collect();
+        Solution { cost: self.cost, trees }
+    }
+}
+
+/// Returned when a query string cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseQueryError {
+    pub message: String,
+}
+
+impl fmt::Display for ParseQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseQueryError {}
+
+fn error<T>(message: impl Into<String>) -> Result<T, ParseQueryError> {
+    Err(ParseQueryError { message: message.into() })
+}
+
+impl FromStr for Query {
+    type Err = ParseQueryError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let spaced = text.replace('(', " ( ").replace(')', " ) ");
+        let tokens: Vec<&str> = spaced.split_whitespace().collect();
+        let mut parser = QueryParser { tokens: &tokens, next: 0 };
+        let query = parser.or()?;
+        match parser.tokens.get(parser.next) {
+            None => Ok(query),
+            Some(token) => error(format!("unexpected '{token}'")),
+        }
+    }
+}
+
+struct QueryParser<'a> {
+    tokens: &'a [&'a str],
+    next: usize,
+}
+
+impl QueryParser<'_> {
+    fn eat(&mut self, keyword: &str) -> bool {
+        let found = self.tokens.get(self.next).is_some_and(|token| token.eq_ignore_ascii_case(keyword));
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Query, ParseQueryError> {
+        let mut terms = vec![self.and()?];
+        while self.eat("or") {
+            terms.push(self.and()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Query::Or(terms) })
+    }
+
+    fn and(&mut self) -> Result<Query, ParseQueryError> {
+        let mut terms = vec![self.unary()?];
+        while self.eat("and") {
+            terms.push(self.unary()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Query::And(terms) })
+    }
+
+    fn unary(&mut self) -> Result<Query, ParseQueryError> {
+        if self.eat("not") {
+            return Ok(Query::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let query = self.or()?;
+            if !self.eat(")") {
+                return error("expected ')'");
+            }
+            return Ok(query);
+        }
+        let Some(token) = self.tokens.get(self.next) else {
+            return error("expected a term, found end of query");
+        };
+        self.next += 1;
+        parse_term(token)
+    }
+}
+
+fn parse_term(token: &str) -> Result<Query, ParseQueryError> {
+    let Some((head, rest)) = token.split_once(':') else {
+        return error(format!("unknown term '{token}'"));
+    };
+    match head {
+        "root" => Ok(Query::Root(parse_kind(rest)?)),
+        "any" => Ok(Query::Any(parse_kind(rest)?)),
+        "never" => Ok(Query::Not(Box::new(Query::Any(parse_kind(rest)?)))),
+        "at" => {
+            let Some((path, kind)) = rest.split_once(':') else {
+                return error(format!("'{token}' should be at:PATH:KIND"));
+            };
+            Ok(Query::At(parse_path(path)?, parse_kind(kind)?))
+        }
+        "word" => parse_word(rest),
+        _ => error(format!("unknown term '{token}'")),
+    }
+}
+
+fn parse_kind(text: &str) -> Result<NodeKind, ParseQueryError> {
+    if let Some(letter) = text.strip_prefix("letter:") {
+        let mut chars = letter.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(letter), None) => Ok(NodeKind::Letter(letter.to_ascii_lowercase())),
+            _ => error(format!("'letter:{letter}' should name a single letter")),
+        };
+    }
+    Ok(match text {
+        "leaf" => NodeKind::Leaf,
+        "repeat" => NodeKind::Repeat,
+        "split" => NodeKind::Split,
+        "yes-split" => NodeKind::YesSplit,
+        "hard" => NodeKind::Hard,
+        "soft" => NodeKind::Soft,
+        "contains" => NodeKind::Position(Position::Contains),
+        "first" => NodeKind::Position(Position::First),
+        "second" => NodeKind::Position(Position::Second),
+        "third" => NodeKind::Position(Position::Third),
+        "third-to-last" => NodeKind::Position(Position::ThirdToLast),
+        "second-to-last" => NodeKind::Position(Position::SecondToLast),
+        "last" => NodeKind::Position(Position::Last),
+        "double" => NodeKind::Position(Position::Double),
+        "triple" => NodeKind::Position(Position::Triple),
+        _ => return error(format!("unknown node kind '{text}'")),
+    })
+}
+
+fn parse_path(text: &str) -> Result<Vec<Branch>, ParseQueryError> {
+    if text == "root" {
+        return Ok(Vec::new());
+    }
+    text.split('.')
+        .map(|step| match step {
+            "yes" => Ok(Branch::Yes),
+            "no" => Ok(Branch::No),
+            _ => error(format!("path steps are 'yes' or 'no', found '{step}'")),
+        })
+        .collect()
+}
+
+fn parse_word(text: &str) -> Result<Query, ParseQueryError> {
+    let Some(split) = text.find(['=', '!', '<', '>']) else {
+        return error(format!("'word:{text}' should compare a metric, e.g. word:leo.nos<=1"));
+    };
+    let (subject, condition) = text.split_at(split);
+    let Some((word, metric)) = subject.rsplit_once('.') else {
+        return error(format!("'word:{text}' should name WORD.METRIC"));
+    };
+    let metric = match metric {
+        "questions" => WordMetric::Questions,
+        "nos" => WordMetric::Nos,
+        "hard_nos" => WordMetric::HardNos,
+        _ => return error(format!("unknown word metric '{metric}' (questions, nos or hard_nos)")),
+    };
+    let (comparison, value) = [
+        ("!=", Comparison::Ne),
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("=", Comparison::Eq),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+    ]
+    .into_iter()
+    .find_map(|(op, comparison)| condition.strip_prefix(op).map(|value| (comparison, value)))
+    .ok_or_else(|| ParseQueryError { message: format!("unknown comparison in 'word:{text}'") })?;
+    let value = value.parse().map_err(|_| ParseQueryError { message: format!("expected a number, got '{value}'") })?;
+    Ok(Query::Word { word: word.to_string(), metric, comparison, value })
+}
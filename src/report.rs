@@ -0,0 +1,97 @@
+use serde::Serialize;
+
+use crate::config::RepeatCost;
+use crate::merged::NodeInfo;
+use crate::node::{is_hard_split, Node};
+
+/// Answer expected from the spectator at one step of a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Answer {
+    Yes,
+    No,
+}
+
+/// One question asked on the way to a word
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathStep {
+    /// The question (a split, `YesSplit` or Repeat; never a leaf)
+    pub question: NodeInfo,
+    pub answer: Answer,
+}
+
+impl PathStep {
+    /// Whether this step counts as a No edge in `Cost` when Repeats cost `repeat_cost`
+    pub const fn is_no(&self, repeat_cost: RepeatCost) -> bool {
+        match (&self.question, self.answer) {
+            (NodeInfo::PositionalSplit { .. }, Answer::No) => true,
+            (NodeInfo::Repeat { .. }, Answer::No) => repeat_cost.no_edge().is_some(),
+            _ => false,
+        }
+    }
+
+    /// Whether this step counts as a hard No edge in `Cost` when Repeats cost `repeat_cost`
+    pub fn is_hard_no(&self, repeat_cost: RepeatCost) -> bool {
+        match (&self.question, self.answer) {
+            (
+                NodeInfo::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position },
+                Answer::No,
+            ) => is_hard_split(*test_letter, *test_position, *requirement_letter, *requirement_position),
+            (NodeInfo::Repeat { .. }, Answer::No) => repeat_cost.no_edge() == Some(true),
+            _ => false,
+        }
+    }
+}
+
+/// Everything the performer asks for one word, in order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordPath {
+    pub word: String,
+    pub steps: Vec<PathStep>,
+    /// Hard No edges on this path (its contribution to `Cost::hard_nos` and `sum_hard_nos`)
+    pub hard_nos: u32,
+    /// No edges on this path (its contribution to `Cost::nos` and `sum_nos`)
+    pub nos: u32,
+}
+
+impl WordPath {
+    /// Number of questions asked, including `YesSplit`s and Repeats
+    pub fn questions(&self) -> u32 {
+        self.steps.len() as u32
+    }
+}
+
+/// Walk a tree and report the path to every word, in Yes-first order, counting the No edge
+/// of each Repeat as `repeat_cost` says (as `tree_cost_with_repeats` does).
+pub fn word_paths(tree: &Node, repeat_cost: RepeatCost) -> Vec<WordPath> {
+    fn walk(node: &Node, repeat_cost: RepeatCost, steps: &mut Vec<PathStep>, out: &mut Vec<WordPath>) {
+        let descend = |child: &Node, answer: Answer, steps: &mut Vec<PathStep>, out: &mut Vec<WordPath>| {
+            steps.push(PathStep { question: NodeInfo::from_node(node), answer });
+            walk(child, repeat_cost, steps, out);
+            steps.pop();
+        };
+
+        match node {
+            Node::Leaf(word) => {
+                let hard_nos = steps.iter().filter(|step| step.is_hard_no(repeat_cost)).count() as u32;
+                let nos = steps.iter().filter(|step| step.is_no(repeat_cost)).count() as u32;
+                out.push(WordPath { word: word.clone(), steps: steps.clone(), hard_nos, nos });
+            }
+            Node::Repeat { word, no } => {
+                descend(&Node::Leaf(word.clone()), Answer::Yes, steps, out);
+                descend(no, Answer::No, steps, out);
+            }
+            Node::PositionalSplit { yes, no, .. } => {
+                descend(yes, Answer::Yes, steps, out);
+                descend(no, Answer::No, steps, out);
+            }
+            Node::YesSplit { yes, .. } => descend(yes, Answer::Yes, steps, out),
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(tree, repeat_cost, &mut Vec::new(), &mut out);
+    out
+}
@@ -0,0 +1,205 @@
+use crate::constraints::soft_no_reason;
+use crate::format::{capitalize_first, format_position_question};
+use crate::node::{is_hard_split, Node, Position};
+
+/// Patter templates used by `format_script`.
+///
+/// Placeholders: `{letter}` (tested letter), `{other}` (letter the No branch has instead),
+/// `{where}` (where the letter is tested, or where the No branch has it in miss lines),
+/// `{reason}` (why a soft pair is confusable), `{question}` (the rendered split question)
+/// and `{word}` (capitalized word).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptTemplates {
+    /// Anywhere in the word
+    pub contains: String,
+    /// First, second or third letter
+    pub near_start: String,
+    /// Last, second-to-last or third-to-last letter
+    pub near_end: String,
+    pub double: String,
+    pub triple: String,
+    /// A question everyone left answers Yes to, wrapping `{question}`
+    pub yes_split: String,
+    /// Each further question of a chain of `YesSplit`s, appended to the same line
+    pub yes_split_continued: String,
+    /// The "repeat in your mind" ploy
+    pub repeat: String,
+    /// No to a hard split
+    pub hard_miss: String,
+    /// No to a soft split over a reciprocal letter pair
+    pub letter_miss: String,
+    /// No to a soft split over the same letter elsewhere in the word
+    pub position_miss: String,
+    /// No to a soft split over another doubled or tripled letter
+    pub repeated_letter_miss: String,
+    /// Final line once a single word remains
+    pub reveal: String,
+    /// Append the exact question in brackets after each line of patter, for rehearsal
+    pub cues: bool,
+}
+
+impl Default for ScriptTemplates {
+    fn default() -> Self {
+        ScriptTemplates {
+            contains: "I'm getting a letter {letter} somewhere in there…".to_string(),
+            near_start: "I'm seeing a strong letter {letter} near the start…".to_string(),
+            near_end: "There's a letter {letter} towards the end, isn't there?".to_string(),
+            double: "I sense a letter repeated… a double {letter}?".to_string(),
+            triple: "Strange, I keep seeing {letter} again and again…".to_string(),
+            yes_split: "And I'm certain of this one. {question}".to_string(),
+            yes_split_continued: "and {question}".to_string(),
+            repeat: "Just repeat it in your mind, over and over… {word}?".to_string(),
+            hard_miss: "No? Then let me try something else.".to_string(),
+            letter_miss: "No? Then it's the {other}, {reason}.".to_string(),
+            position_miss: "No? But there is the letter {letter}, {where}, I was just a little off.".to_string(),
+            repeated_letter_miss: "No? Still, there is a repeated letter, a {other}.".to_string(),
+            reveal: "You're thinking of {word}!".to_string(),
+            cues: true,
+        }
+    }
+}
+
+impl ScriptTemplates {
+    const fn question_template(&self, position: Position) -> &String {
+        match position {
+            Position::Contains => &self.contains,
+            Position::First | Position::Second | Position::Third => &self.near_start,
+            Position::ThirdToLast | Position::SecondToLast | Position::Last => &self.near_end,
+            Position::Double => &self.double,
+            Position::Triple => &self.triple,
+        }
+    }
+}
+
+/// Where a letter sits, phrased for a miss line
+const fn location_phrase(position: Position) -> &'static str {
+    match position {
+        Position::Contains => "somewhere",
+        Position::First => "right at the start",
+        Position::Second => "second",
+        Position::Third => "third",
+        Position::ThirdToLast => "third from the end",
+        Position::SecondToLast => "second from the end",
+        Position::Last => "right at the end",
+        Position::Double => "doubled",
+        Position::Triple => "tripled",
+    }
+}
+
+fn fill(template: &str, letter: char, other: char, location: &str, reason: &str) -> String {
+    template
+        .replace("{letter}", &letter.to_ascii_uppercase().to_string())
+        .replace("{other}", &other.to_ascii_uppercase().to_string())
+        .replace("{where}", location)
+        .replace("{reason}", reason)
+}
+
+/// `text` lowered to continue a sentence, leaving the pronoun I alone
+fn continue_sentence(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some('I') if matches!(chars.next(), None | Some(' ' | '\'')) => text.to_string(),
+        Some(first) => first.to_lowercase().chain(text[first.len_utf8()..].chars()).collect(),
+        None => String::new(),
+    }
+}
+
+/// Patter for a split, followed by its exact question when cues are enabled
+fn question_line(
+    templates: &ScriptTemplates,
+    test_letter: char,
+    test_position: Position,
+    requirement_letter: char,
+    requirement_position: Position,
+) -> String {
+    let template = templates.question_template(test_position);
+    let patter = fill(template, test_letter, requirement_letter, location_phrase(test_position), "");
+    if templates.cues {
+        let cue = format_position_question(test_letter, &test_position, requirement_letter, &requirement_position);
+        format!("{patter} [{cue}]")
+    } else {
+        patter
+    }
+}
+
+/// Justification spoken when the spectator answers No to a split
+fn miss_line(
+    templates: &ScriptTemplates,
+    test_letter: char,
+    test_position: Position,
+    requirement_letter: char,
+    requirement_position: Position,
+) -> String {
+    let template = if is_hard_split(test_letter, test_position, requirement_letter, requirement_position) {
+        &templates.hard_miss
+    } else if test_letter == requirement_letter {
+        &templates.position_miss
+    } else if let Some(reason) = soft_no_reason(test_letter, requirement_letter) {
+        return fill(&templates.letter_miss, test_letter, requirement_letter, "", reason);
+    } else {
+        // Different letters without a reciprocal pair only happen for Double and Triple
+        &templates.repeated_letter_miss
+    };
+    fill(template, test_letter, requirement_letter, location_phrase(requirement_position), "")
+}
+
+/// Render a tree as a performance script: what to say at each step and what to say
+/// after each answer, indented by branch.
+pub fn format_script(node: &Node, templates: &ScriptTemplates) -> String {
+    fn say(line: &str, indent: usize, out: &mut String) {
+        out.push_str(&"  ".repeat(indent));
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    fn render(node: &Node, templates: &ScriptTemplates, indent: usize, out: &mut String) {
+        match node {
+            Node::Leaf(word) => {
+                say(&templates.reveal.replace("{word}", &capitalize_first(word)), indent, out);
+            }
+            Node::Repeat { word, no } => {
+                say(&templates.repeat.replace("{word}", &capitalize_first(word)), indent, out);
+                say("If yes:", indent, out);
+                render(&Node::Leaf(word.clone()), templates, indent + 1, out);
+                say("If no:", indent, out);
+                render(no, templates, indent + 1, out);
+            }
+            Node::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position, yes, no } => {
+                let patter =
+                    question_line(templates, *test_letter, *test_position, *requirement_letter, *requirement_position);
+                say(&patter, indent, out);
+                say("If yes:", indent, out);
+                render(yes, templates, indent + 1, out);
+                say("If no:", indent, out);
+                let miss =
+                    miss_line(templates, *test_letter, *test_position, *requirement_letter, *requirement_position);
+                say(&miss, indent + 1, out);
+                render(no, templates, indent + 1, out);
+            }
+            Node::YesSplit { .. } => {
+                // A chain of YesSplits is one line, each further question continuing the last
+                let mut line = String::new();
+                let mut node = node;
+                while let Node::YesSplit { test_letter, test_position, requirement_letter, requirement_position, yes } =
+                    node
+                {
+                    let (letter, position) = (*requirement_letter, *requirement_position);
+                    let question = question_line(templates, *test_letter, *test_position, letter, position);
+                    if line.is_empty() {
+                        line = templates.yes_split.replace("{question}", &question);
+                    } else {
+                        let question = continue_sentence(&question);
+                        line = format!("{line} {}", templates.yes_split_continued.replace("{question}", &question));
+                    }
+                    node = yes;
+                }
+                say(&line, indent, out);
+                render(node, templates, indent, out);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    render(node, templates, 0, &mut out);
+    out
+}
@@ -0,0 +1,108 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use hashbrown::HashMap;
+use serde::Serialize;
+
+use crate::api::solve_subset;
+use crate::config::SolverConfig;
+use crate::context::{Context, Mask};
+use crate::cost::Cost;
+
+/// How one word affects the cost of its set
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WordSensitivity {
+    pub word: String,
+    /// Best cost of the set without this word, `None` if the rest has no tree
+    pub without: Option<Cost>,
+    /// Best cost with this word swapped for the alternative, `None` without an
+    /// alternative or when the swapped set has no tree
+    pub replaced: Option<Cost>,
+}
+
+/// Result of `sensitivity`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SensitivityReport {
+    /// Best cost of the full set, `None` if it has no tree
+    pub cost: Option<Cost>,
+    pub alternative: Option<String>,
+    /// Every word, the one whose removal lowers the cost most first
+    pub words: Vec<WordSensitivity>,
+}
+
+/// Returned when the words cannot be analysed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SensitivityError {
+    /// Removing a word from a single-word set leaves nothing to solve.
+    TooFewWords,
+    /// The words plus the alternative exceed the solver's 32-word bitmasks.
+    TooManyWords(usize),
+    DuplicateWord(String),
+}
+
+impl fmt::Display for SensitivityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SensitivityError::TooFewWords => write!(f, "sensitivity needs at least two words"),
+            SensitivityError::TooManyWords(count) => {
+                write!(f, "{count} words including the alternative, the solver supports up to 32")
+            }
+            SensitivityError::DuplicateWord(word) => write!(f, "'{word}' appears more than once"),
+        }
+    }
+}
+
+impl std::error::Error for SensitivityError {}
+
+/// Re-solve `words` with each word removed, and with each word replaced by `alternative` when
+/// given, ranking the words by how much cheaper the set gets without them.
+/// Every variant is a sub-mask of one `Context`, so they all share one memo.
+pub fn sensitivity(
+    words: &[String],
+    alternative: Option<&str>,
+    config: &SolverConfig,
+) -> Result<SensitivityReport, SensitivityError> {
+    if words.len() < 2 {
+        return Err(SensitivityError::TooFewWords);
+    }
+    let mut pool = words.to_vec();
+    pool.extend(alternative.map(str::to_string));
+    if pool.len() > 32 {
+        return Err(SensitivityError::TooManyWords(pool.len()));
+    }
+    for (idx, word) in pool.iter().enumerate() {
+        if pool[..idx].contains(word) {
+            return Err(SensitivityError::DuplicateWord(word.clone()));
+        }
+    }
+
+    let ctx = Context::new(&pool);
+    let mut memo = HashMap::new();
+    let mut cost_of = |mask: Mask| {
+        let sol = solve_subset(&ctx, mask, config, config.max_depth, &mut memo);
+        (!sol.is_unsolvable()).then_some(sol.cost)
+    };
+    let full = if words.len() == 32 { Mask::MAX } else { ((1 as Mask) << words.len()) - 1 };
+    let cost = cost_of(full);
+    let mut impacts: Vec<WordSensitivity> = words
+        .iter()
+        .enumerate()
+        .map(|(idx, word)| {
+            let without = full & !(1 << idx);
+            WordSensitivity {
+                word: word.clone(),
+                without: cost_of(without),
+                replaced: alternative.and_then(|_| cost_of(without | 1 << words.len())),
+            }
+        })
+        .collect();
+
+    // Stable sort keeps list order among words with the same impact; unsolvable variants go last
+    impacts.sort_by(|a, b| match (&a.without, &b.without) {
+        (Some(a), Some(b)) => config.compare(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    Ok(SensitivityReport { cost, alternative: alternative.map(str::to_string), words: impacts })
+}
@@ -0,0 +1,99 @@
+use hashbrown::HashMap;
+use serde::Serialize;
+
+use crate::api::{try_solve, DepthInfeasible};
+use crate::config::SolverConfig;
+use crate::context::Context;
+use crate::dijkstra_solver::Key;
+use crate::node::Solution;
+
+/// Where a `StepSolver` stands after a call to `step`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Progress {
+    /// Subproblems expanded so far, over all steps
+    pub expanded: u64,
+    /// Subproblems solved for good and kept in the memo (freed once done)
+    pub memo_entries: usize,
+    pub done: bool,
+    pub cancelled: bool,
+}
+
+/// Runs the solver a bounded amount of work at a time, so a caller without threads (such as
+/// the web UI) can show progress between steps and stop at any time.
+///
+/// Each step restarts the search from the root with a limited number of new subproblems to
+/// expand. Subproblems finished in earlier steps are memo hits, so every step makes progress;
+/// the price is re-walking the memoized part of the search, which is cheap next to expanding it.
+pub struct StepSolver {
+    words: Vec<String>,
+    aliases: Vec<Vec<String>>,
+    config: SolverConfig,
+    memo: HashMap<Key, Solution>,
+    expanded: u64,
+    result: Option<Result<Solution, DepthInfeasible>>,
+    cancelled: bool,
+}
+
+impl StepSolver {
+    /// Panics with more than 32 words, like the other solver entry points.
+    pub fn new(words: Vec<String>, config: SolverConfig) -> Self {
+        assert!(words.len() <= 32, "bitmask solver supports up to 32 words");
+        StepSolver {
+            words,
+            aliases: Vec::new(),
+            config,
+            memo: HashMap::new(),
+            expanded: 0,
+            result: None,
+            cancelled: false,
+        }
+    }
+
+    /// Alternate spellings, as in `minimal_trees_with_aliases`
+    pub fn with_aliases(mut self, aliases: Vec<Vec<String>>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Expand at most `budget` new subproblems (at least one), finishing the search if they suffice.
+    pub fn step(&mut self, budget: u64) -> Progress {
+        if self.result.is_none() && !self.cancelled {
+            let budget = budget.max(1);
+            let ctx = Context::with_aliases(&self.words, &self.aliases);
+            ctx.limit_steps(Some(budget));
+            let result = try_solve(&ctx, &self.config, &mut self.memo);
+            self.expanded += budget - ctx.steps_left().unwrap_or(0);
+            if !ctx.interrupted() {
+                self.result = Some(result);
+                self.memo = HashMap::new();
+            }
+        }
+        self.progress()
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            expanded: self.expanded,
+            memo_entries: self.memo.len(),
+            done: self.result.is_some(),
+            cancelled: self.cancelled,
+        }
+    }
+
+    /// Stop the search and free its memo; later steps do nothing.
+    pub fn cancel(&mut self) {
+        if self.result.is_none() {
+            self.cancelled = true;
+        }
+        self.memo = HashMap::new();
+    }
+
+    pub const fn config(&self) -> &SolverConfig {
+        &self.config
+    }
+
+    /// The result of `try_minimal_trees`, once the search is done
+    pub const fn result(&self) -> Option<&Result<Solution, DepthInfeasible>> {
+        self.result.as_ref()
+    }
+}
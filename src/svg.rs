@@ -0,0 +1,221 @@
+use std::fmt::Write;
+
+use crate::format::{capitalize_first, format_position_question};
+use crate::node::{is_hard_split, Node};
+
+/// Options for `render_svg`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// Scale the drawing to fit this page (width, height), keeping its aspect ratio.
+    /// `None` keeps the natural size.
+    pub page_size: Option<(f64, f64)>,
+    pub font_size: f64,
+    /// Print the words still possible under each question
+    pub show_word_sets: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions { page_size: None, font_size: 14.0, show_word_sets: false }
+    }
+}
+
+impl SvgOptions {
+    /// A4 portrait, in points
+    pub const A4: (f64, f64) = (595.0, 842.0);
+    /// US Letter portrait, in points
+    pub const LETTER: (f64, f64) = (612.0, 792.0);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NoStyle {
+    Hard,
+    /// Soft No edges, and the No edge of a Repeat (drawn soft whatever its `RepeatCost`)
+    Soft,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextStyle {
+    Question,
+    YesSplit,
+    Word,
+}
+
+/// A node placed on the grid: one row per node, one column per No branch taken
+struct Placed {
+    column: usize,
+    row: usize,
+    label: String,
+    style: TextStyle,
+    words: Vec<String>,
+}
+
+#[derive(Default)]
+struct Layout {
+    nodes: Vec<Placed>,
+    /// Vertical spine segments (parent, yes child)
+    yes_edges: Vec<(usize, usize)>,
+    /// Elbows jutting right (parent, no child, style)
+    no_edges: Vec<(usize, usize, NoStyle)>,
+}
+
+impl Layout {
+    /// Place `node` at `column` on the next free row, then its No branch one column to
+    /// the right, then its Yes branch below on the same column (like `format_tree`).
+    fn place(&mut self, node: &Node, column: usize) -> usize {
+        let (label, style) = match node {
+            Node::Leaf(word) => (capitalize_first(word), TextStyle::Word),
+            Node::Repeat { word, .. } => {
+                let word = capitalize_first(word);
+                (format!("Repeat {word}, {word}, {word}..."), TextStyle::Question)
+            }
+            Node::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position, .. } => (
+                format_position_question(*test_letter, test_position, *requirement_letter, requirement_position),
+                TextStyle::Question,
+            ),
+            Node::YesSplit { test_letter, test_position, requirement_letter, requirement_position, .. } => {
+                let question =
+                    format_position_question(*test_letter, test_position, *requirement_letter, requirement_position);
+                (format!("{question} (yes only)"), TextStyle::YesSplit)
+            }
+        };
+        let id = self.nodes.len();
+        self.nodes.push(Placed { column, row: id, label, style, words: leaf_words(node) });
+
+        let (yes, no, no_style) = match node {
+            Node::Leaf(_) => return id,
+            Node::Repeat { word, no } => (Node::Leaf(word.clone()), Some(no), NoStyle::Soft),
+            Node::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position, yes, no } => {
+                let hard = is_hard_split(*test_letter, *test_position, *requirement_letter, *requirement_position);
+                ((**yes).clone(), Some(no), if hard { NoStyle::Hard } else { NoStyle::Soft })
+            }
+            Node::YesSplit { yes, .. } => ((**yes).clone(), None, NoStyle::Soft),
+        };
+        if let Some(no) = no {
+            let no_id = self.place(no, column + 1);
+            self.no_edges.push((id, no_id, no_style));
+        }
+        let yes_id = self.place(&yes, column);
+        self.yes_edges.push((id, yes_id));
+        id
+    }
+}
+
+fn leaf_words(node: &Node) -> Vec<String> {
+    match node {
+        Node::Leaf(word) => vec![word.clone()],
+        Node::Repeat { word, no } => std::iter::once(word.clone()).chain(leaf_words(no)).collect(),
+        Node::PositionalSplit { yes, no, .. } => leaf_words(yes).into_iter().chain(leaf_words(no)).collect(),
+        Node::YesSplit { yes, .. } => leaf_words(yes),
+    }
+}
+
+/// Round coordinates to hundredths so the output stays readable
+fn px(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Render a tree as a standalone SVG cheat sheet, following the spine layout of
+/// `format_tree`: Yes runs down the page and No branches jut to the right.
+/// Hard No edges are solid red, soft No edges dashed orange.
+pub fn render_svg(node: &Node, options: &SvgOptions) -> String {
+    let mut layout = Layout::default();
+    layout.place(node, 0);
+
+    let font = options.font_size;
+    let indent = font * 1.5;
+    let margin = font;
+    let line_height = font * 1.6;
+    let row_height = if options.show_word_sets { line_height * 1.9 } else { line_height };
+    // Rough average glyph width; only used to size the canvas
+    let char_width = font * 0.6;
+
+    let x_of = |placed: &Placed| margin + placed.column as f64 * indent;
+    let y_of = |placed: &Placed| margin + placed.row as f64 * row_height + font;
+    let word_set = |placed: &Placed| {
+        let words: Vec<String> = placed.words.iter().map(|w| capitalize_first(w)).collect();
+        format!("{{{}}}", words.join(", "))
+    };
+
+    let mut width: f64 = 0.0;
+    for placed in &layout.nodes {
+        let mut chars = placed.label.chars().count();
+        if options.show_word_sets && placed.style != TextStyle::Word {
+            chars = chars.max((word_set(placed).chars().count() as f64 * 0.85).ceil() as usize);
+        }
+        width = width.max(x_of(placed) + chars as f64 * char_width);
+    }
+    let width = (width + margin).ceil();
+    let height = (layout.nodes.len() as f64 * row_height + 2.0 * margin).ceil();
+    let (page_width, page_height) = options.page_size.unwrap_or((width, height));
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{page_width}\" height=\"{page_height}\" \
+         viewBox=\"0 0 {width} {height}\" preserveAspectRatio=\"xMidYMin meet\" \
+         font-family=\"Helvetica, Arial, sans-serif\" font-size=\"{font}\">"
+    );
+    out.push_str("  <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n");
+
+    // Spine: from below the parent's text down to the Yes child
+    for &(parent, child) in &layout.yes_edges {
+        let (parent, child) = (&layout.nodes[parent], &layout.nodes[child]);
+        let x = px(x_of(parent) + font * 0.3);
+        let _ = writeln!(
+            out,
+            "  <line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\" stroke=\"#2e7d32\" stroke-width=\"1.5\"/>",
+            px(y_of(parent) + font * 0.4),
+            px(y_of(child) - font)
+        );
+    }
+
+    // No branches: down alongside the spine, then right to the child
+    for &(parent, child, style) in &layout.no_edges {
+        let (parent, child) = (&layout.nodes[parent], &layout.nodes[child]);
+        let x = px(x_of(parent) + font * 0.3 + indent * 0.5);
+        let y = px(y_of(child) - font * 0.35);
+        let stroke = match style {
+            NoStyle::Hard => "stroke=\"#c62828\" stroke-width=\"2\"",
+            NoStyle::Soft => "stroke=\"#ef6c00\" stroke-width=\"1.5\" stroke-dasharray=\"4 3\"",
+        };
+        let _ = writeln!(
+            out,
+            "  <path d=\"M {x} {} V {y} H {}\" fill=\"none\" {stroke}/>",
+            px(y_of(parent) + font * 0.4),
+            px(x_of(child) - font * 0.2)
+        );
+    }
+
+    for placed in &layout.nodes {
+        let style = match placed.style {
+            TextStyle::Question => "",
+            TextStyle::YesSplit => " font-style=\"italic\"",
+            TextStyle::Word => " font-weight=\"bold\"",
+        };
+        let _ = writeln!(
+            out,
+            "  <text x=\"{}\" y=\"{}\"{style}>{}</text>",
+            px(x_of(placed)),
+            px(y_of(placed)),
+            escape_xml(&placed.label)
+        );
+        if options.show_word_sets && placed.style != TextStyle::Word {
+            let _ = writeln!(
+                out,
+                "  <text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"#757575\">{}</text>",
+                px(x_of(placed)),
+                px(y_of(placed) + line_height * 0.85),
+                px(font * 0.85),
+                escape_xml(&word_set(placed))
+            );
+        }
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
@@ -0,0 +1,227 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::config::RepeatCost;
+use crate::cost::{add_no_edge, add_questions, add_repeat_edge, add_yes_split, Cost};
+use crate::format::format_position_question;
+use crate::node::{is_hard_split, Node, Position};
+
+/// A problem found by `validate_tree`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeIssue {
+    /// The word is reached by more than one path
+    DuplicateWord(String),
+    /// The word is in the expected list but not in the tree
+    MissingWord(String),
+    /// The word is in the tree but not in the expected list
+    UnexpectedWord(String),
+    /// A word reaching the Yes branch (or a `YesSplit`) fails the question
+    FailsQuestion { question: String, word: String },
+    /// A word reaching the No branch passes the question
+    PassesQuestion { question: String, word: String },
+    /// A word reaching the No branch of a soft split lacks the requirement
+    FailsRequirement { question: String, word: String },
+}
+
+impl fmt::Display for TreeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeIssue::DuplicateWord(word) => write!(f, "'{word}' appears more than once"),
+            TreeIssue::MissingWord(word) => write!(f, "'{word}' is missing from the tree"),
+            TreeIssue::UnexpectedWord(word) => write!(f, "'{word}' is not in the word list"),
+            TreeIssue::FailsQuestion { question, word } => {
+                write!(f, "'{word}' is on the Yes side of \"{question}\" but answers No")
+            }
+            TreeIssue::PassesQuestion { question, word } => {
+                write!(f, "'{word}' is on the No side of \"{question}\" but answers Yes")
+            }
+            TreeIssue::FailsRequirement { question, word } => {
+                write!(f, "'{word}' is on the No side of \"{question}\" but lacks the requirement")
+            }
+        }
+    }
+}
+
+/// Whether `word` has `letter` at `position`, with the same rules as the solver
+/// (case-insensitive; Double and Triple count occurrences anywhere in the word).
+pub fn word_matches(word: &str, letter: char, position: Position) -> bool {
+    let letter = letter.to_ascii_lowercase();
+    let chars: Vec<char> = word.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let count = || chars.iter().filter(|&&c| c == letter).count();
+    match position {
+        Position::Contains => chars.contains(&letter),
+        Position::Double => count() >= 2,
+        Position::Triple => count() >= 3,
+        _ => position.to_absolute_index(chars.len()).is_some_and(|idx| chars[idx] == letter),
+    }
+}
+
+/// Words reached by each leaf of `node`, in Yes-first order
+fn leaf_words(node: &Node) -> Vec<&str> {
+    match node {
+        Node::Leaf(word) => vec![word],
+        Node::Repeat { word, no } => std::iter::once(word.as_str()).chain(leaf_words(no)).collect(),
+        Node::PositionalSplit { yes, no, .. } => leaf_words(yes).into_iter().chain(leaf_words(no)).collect(),
+        Node::YesSplit { yes, .. } => leaf_words(yes),
+    }
+}
+
+/// Check that every question of a saved tree sorts the words reaching it correctly:
+/// Yes words pass the test, No words fail it and meet the requirement of a soft split,
+/// and every word under a `YesSplit` passes it. With `words`, also check that the tree
+/// reaches exactly those words. Letter-reuse constraints are not checked.
+pub fn validate_tree(tree: &Node, words: Option<&[String]>) -> Vec<TreeIssue> {
+    fn check(node: &Node, issues: &mut Vec<TreeIssue>) {
+        match node {
+            Node::Leaf(_) => {}
+            Node::Repeat { no, .. } => check(no, issues),
+            Node::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position, yes, no } => {
+                let question =
+                    format_position_question(*test_letter, test_position, *requirement_letter, requirement_position);
+                let hard = is_hard_split(*test_letter, *test_position, *requirement_letter, *requirement_position);
+                for word in leaf_words(yes) {
+                    if !word_matches(word, *test_letter, *test_position) {
+                        issues.push(TreeIssue::FailsQuestion { question: question.clone(), word: word.to_string() });
+                    }
+                }
+                for word in leaf_words(no) {
+                    if word_matches(word, *test_letter, *test_position) {
+                        issues.push(TreeIssue::PassesQuestion { question: question.clone(), word: word.to_string() });
+                    } else if !hard && !word_matches(word, *requirement_letter, *requirement_position) {
+                        let word = word.to_string();
+                        issues.push(TreeIssue::FailsRequirement { question: question.clone(), word });
+                    }
+                }
+                check(yes, issues);
+                check(no, issues);
+            }
+            Node::YesSplit { test_letter, test_position, requirement_letter, requirement_position, yes } => {
+                let question =
+                    format_position_question(*test_letter, test_position, *requirement_letter, requirement_position);
+                for word in leaf_words(yes) {
+                    if !word_matches(word, *test_letter, *test_position) {
+                        issues.push(TreeIssue::FailsQuestion { question: question.clone(), word: word.to_string() });
+                    }
+                }
+                check(yes, issues);
+            }
+        }
+    }
+
+    let mut issues = Vec::new();
+    let mut seen = HashSet::new();
+    for word in leaf_words(tree) {
+        if !seen.insert(word) {
+            issues.push(TreeIssue::DuplicateWord(word.to_string()));
+        }
+    }
+    if let Some(words) = words {
+        for word in words {
+            if !seen.contains(word.as_str()) {
+                issues.push(TreeIssue::MissingWord(word.clone()));
+            }
+        }
+        let expected: HashSet<&str> = words.iter().map(String::as_str).collect();
+        for word in leaf_words(tree) {
+            if !expected.contains(word) {
+                issues.push(TreeIssue::UnexpectedWord(word.to_string()));
+            }
+        }
+    }
+    check(tree, &mut issues);
+    issues
+}
+
+/// Cost of a given tree, computed the way the solver scores the trees it builds
+/// (`YesSplit`s directly below a No edge redeem that edge).
+pub fn tree_cost(tree: &Node, redeeming_yes: u32) -> Cost {
+    tree_cost_with_repeats(tree, redeeming_yes, RepeatCost::Free)
+}
+
+/// `tree_cost` with the No edge of every Repeat costing `repeat_cost`.
+pub fn tree_cost_with_repeats(tree: &Node, redeeming_yes: u32, repeat_cost: RepeatCost) -> Cost {
+    let redeeming = redeeming_yes as i32;
+    match tree {
+        Node::Leaf(_) => Cost {
+            hard_nos: 0,
+            redeemed_hard_nos: 0,
+            nos: 0,
+            redeemed_nos: 0,
+            sum_hard_nos: 0,
+            redeemed_sum_hard_nos: 0,
+            sum_nos: 0,
+            redeemed_sum_nos: 0,
+            max_consecutive_nos: 0,
+            leading_nos: 0,
+            max_questions: 0,
+            sum_questions: 0,
+            word_count: 1,
+        },
+        Node::Repeat { no, .. } => {
+            // The repeated word itself costs one question and nothing else
+            let no_sol = tree_cost_with_repeats(no, redeeming_yes, repeat_cost);
+            let no = add_repeat_edge(&no_sol, repeat_cost.no_edge(), redeeming);
+            let no_questions = add_questions(&no_sol, 1);
+            Cost {
+                redeemed_hard_nos: no.redeemed_hard_nos.max(0),
+                redeemed_nos: no.redeemed_nos.max(0),
+                max_questions: no_questions.max_questions.max(1),
+                sum_questions: no_questions.sum_questions + 1,
+                word_count: no.word_count + 1,
+                ..no
+            }
+        }
+        Node::PositionalSplit { test_letter, test_position, requirement_letter, requirement_position, yes, no } => {
+            let is_hard = is_hard_split(*test_letter, *test_position, *requirement_letter, *requirement_position);
+
+            // Peel the YesSplits sitting between the No edge and the rest of the branch
+            let mut inner = &**no;
+            let mut yes_splits = 0;
+            while let Node::YesSplit { yes, .. } = inner {
+                inner = yes;
+                yes_splits += 1;
+            }
+            let no_sol = tree_cost_with_repeats(inner, redeeming_yes, repeat_cost);
+            let yes_sol = tree_cost_with_repeats(yes, redeeming_yes, repeat_cost);
+
+            let mut no_cost = no_sol;
+            for _ in 0..yes_splits {
+                no_cost = add_yes_split(&no_cost);
+            }
+            no_cost = add_no_edge(&no_cost, is_hard, redeeming);
+            no_cost.sum_nos += no_sol.word_count;
+            no_cost.redeemed_sum_nos += no_sol.word_count as i32 * redeeming;
+            if is_hard {
+                no_cost.sum_hard_nos += no_sol.word_count;
+                no_cost.redeemed_sum_hard_nos += no_sol.word_count as i32 * redeeming;
+            }
+            no_cost.redeemed_hard_nos = no_cost.redeemed_hard_nos.max(0);
+            no_cost.redeemed_nos = no_cost.redeemed_nos.max(0);
+            no_cost.redeemed_sum_hard_nos = no_cost.redeemed_sum_hard_nos.max(0);
+            no_cost.redeemed_sum_nos = no_cost.redeemed_sum_nos.max(0);
+
+            let yes_cost = add_yes_split(&yes_sol);
+            let yes_questions = add_questions(&yes_sol, 1);
+            let no_questions = add_questions(&no_sol, 1 + yes_splits);
+            Cost {
+                hard_nos: yes_cost.hard_nos.max(no_cost.hard_nos),
+                redeemed_hard_nos: yes_cost.redeemed_hard_nos.max(no_cost.redeemed_hard_nos),
+                nos: yes_cost.nos.max(no_cost.nos),
+                redeemed_nos: yes_cost.redeemed_nos.max(no_cost.redeemed_nos),
+                sum_hard_nos: yes_sol.sum_hard_nos + no_cost.sum_hard_nos,
+                redeemed_sum_hard_nos: yes_sol.redeemed_sum_hard_nos + no_cost.redeemed_sum_hard_nos,
+                sum_nos: yes_sol.sum_nos + no_cost.sum_nos,
+                redeemed_sum_nos: yes_sol.redeemed_sum_nos + no_cost.redeemed_sum_nos,
+                max_consecutive_nos: yes_cost.max_consecutive_nos.max(no_cost.max_consecutive_nos),
+                leading_nos: yes_cost.leading_nos.max(no_cost.leading_nos),
+                max_questions: yes_questions.max_questions.max(no_questions.max_questions),
+                sum_questions: yes_questions.sum_questions + no_questions.sum_questions,
+                word_count: yes_sol.word_count + no_sol.word_count,
+            }
+        }
+        // The solver only places YesSplits below No edges; elsewhere they just redeem and ask
+        Node::YesSplit { yes, .. } => {
+            add_questions(&add_yes_split(&tree_cost_with_repeats(yes, redeeming_yes, repeat_cost)), 1)
+        }
+    }
+}
@@ -2,9 +2,24 @@ use serde::Serialize;
 use serde_wasm_bindgen::{from_value, to_value};
 use wasm_bindgen::prelude::*;
 
-use crate::node::Solution;
-use crate::api::minimal_trees;
-use crate::merged::MergedNode;
+use crate::node::{Node, Solution};
+use crate::api::{try_minimal_trees, DepthInfeasible};
+use crate::config::{RepeatCost, SolverConfig};
+use crate::cost::Cost;
+use crate::diff::{diff, format_diff, TreeDiff};
+use crate::format::{format_tree, format_word_paths};
+use crate::merged::{Branch, MergedNode};
+use crate::partition::group_by_partition;
+use crate::query::Query;
+use crate::report::{word_paths, WordPath};
+use crate::script::{format_script, ScriptTemplates};
+use crate::stepper::StepSolver;
+use crate::svg::{render_svg, SvgOptions};
+use crate::validate::{tree_cost_with_repeats, validate_tree};
+use crate::wordsets;
+
+/// Concrete trees included in a solution object; `tree_count` gives the full number
+const MAX_TREES: usize = 100;
 
 #[derive(Serialize)]
 struct WasmCostSummary {
@@ -12,80 +27,360 @@ struct WasmCostSummary {
     max_nos: u32,
     sum_hard_nos: u32,
     sum_nos: u32,
+    max_consecutive_nos: u32,
+    max_questions: u32,
+    sum_questions: u32,
     word_count: u32,
     avg_hard_nos: f32,
     avg_nos: f32,
+    avg_questions: f32,
 }
 
 #[derive(Serialize)]
 struct WasmSolution {
     cost: WasmCostSummary,
     merged_tree: MergedNode,
+    /// Per-word rehearsal report for the first optimal tree
+    word_paths: Vec<WordPath>,
+    tree_count: usize,
+    /// Classes of `group_by_partition`: trees differing only in the wording of their questions
+    routine_count: usize,
+    /// The first `MAX_TREES` optimal trees, in compact notation
+    trees: Vec<String>,
+    /// `format_tree` text of the first optimal tree
+    text: String,
+}
+
+/// Cost, validity and rendering of a tree edited by the user
+#[derive(Serialize)]
+struct WasmTreeEvaluation {
+    cost: WasmCostSummary,
+    /// `validate_tree` problems, as messages; empty for a valid tree
+    issues: Vec<String>,
+    word_paths: Vec<WordPath>,
+    text: String,
+}
+
+/// A tree picked out of the merged tree by the user's choices
+#[derive(Serialize)]
+struct WasmResolution {
+    /// Compact notation
+    tree: String,
+    text: String,
+    choices: Vec<WasmChoice>,
+    /// Paths of picks that contradict earlier ones
+    ignored: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct WasmChoice {
+    path: String,
+    selected: usize,
+    valid: Vec<usize>,
+}
+
+/// Choice node paths in the web UI's form: `root`, then `_yes` or `_no` for each step down
+fn path_from_js(path: &str) -> Result<Vec<Branch>, JsValue> {
+    let steps = path.strip_prefix("root").ok_or_else(|| JsValue::from_str(&format!("Invalid path: {path}")))?;
+    steps
+        .split('_')
+        .skip(1)
+        .map(|step| match step {
+            "yes" => Ok(Branch::Yes),
+            "no" => Ok(Branch::No),
+            _ => Err(JsValue::from_str(&format!("Invalid path: {path}"))),
+        })
+        .collect()
+}
+
+fn path_to_js(path: &[Branch]) -> String {
+    let mut out = String::from("root");
+    for branch in path {
+        out.push_str(match branch {
+            Branch::Yes => "_yes",
+            Branch::No => "_no",
+        });
+    }
+    out
+}
+
+/// Differences between two trees, with the second rendered and annotated
+#[derive(Serialize)]
+struct WasmTreeDiff {
+    diff: TreeDiff,
+    text: String,
 }
 
 fn words_from_js(value: JsValue) -> Result<Vec<String>, JsValue> {
-    from_value(value).map_err(|e| JsValue::from_str(&format!("Words must be an array of strings: {e}")))
+    let words: Vec<String> =
+        from_value(value).map_err(|e| JsValue::from_str(&format!("Words must be an array of strings: {e}")))?;
+    if words.is_empty() {
+        return Err(JsValue::from_str("Please supply at least one word."));
+    }
+    if words.len() > 32 {
+        return Err(JsValue::from_str("Solver supports up to 32 words."));
+    }
+    Ok(words)
 }
 
-fn summary_from_solution(sol: &Solution) -> WasmSolution {
-    let word_count = sol.cost.word_count;
-    let avg_hard_nos = if word_count == 0 { 0.0 } else { sol.cost.sum_hard_nos as f32 / word_count as f32 };
-    let avg_nos = if word_count == 0 { 0.0 } else { sol.cost.sum_nos as f32 / word_count as f32 };
+fn result_to_js(result: Result<Solution, DepthInfeasible>, repeat_cost: RepeatCost) -> Result<JsValue, JsValue> {
+    let sol = result.map_err(|e| JsValue::from_str(&e.to_string()))?;
+    if sol.is_unsolvable() {
+        return Err(JsValue::from_str("No tree satisfies these constraints."));
+    }
+    to_value(&summary_from_solution(&sol, repeat_cost))
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+fn cost_summary(cost: &Cost) -> WasmCostSummary {
+    let word_count = cost.word_count;
+    let avg_hard_nos = if word_count == 0 { 0.0 } else { cost.sum_hard_nos as f32 / word_count as f32 };
+    let avg_nos = if word_count == 0 { 0.0 } else { cost.sum_nos as f32 / word_count as f32 };
+    let avg_questions = if word_count == 0 { 0.0 } else { cost.sum_questions as f32 / word_count as f32 };
+    WasmCostSummary {
+        max_hard_nos: cost.hard_nos,
+        max_nos: cost.nos,
+        sum_hard_nos: cost.sum_hard_nos,
+        sum_nos: cost.sum_nos,
+        max_consecutive_nos: cost.max_consecutive_nos,
+        max_questions: cost.max_questions,
+        sum_questions: cost.sum_questions,
+        word_count,
+        avg_hard_nos,
+        avg_nos,
+        avg_questions,
+    }
+}
 
+fn summary_from_solution(sol: &Solution, repeat_cost: RepeatCost) -> WasmSolution {
     // Merge all optimal trees into a single navigable structure
     let merged_tree = MergedNode::merge(&sol.trees);
 
     WasmSolution {
-        cost: WasmCostSummary {
-            max_hard_nos: sol.cost.hard_nos,
-            max_nos: sol.cost.nos,
-            sum_hard_nos: sol.cost.sum_hard_nos,
-            sum_nos: sol.cost.sum_nos,
-            word_count,
-            avg_hard_nos,
-            avg_nos,
-        },
+        cost: cost_summary(&sol.cost),
         merged_tree,
+        word_paths: sol.trees.first().map(|tree| word_paths(tree, repeat_cost)).unwrap_or_default(),
+        tree_count: sol.trees.len(),
+        routine_count: group_by_partition(&sol.trees).len(),
+        trees: sol.trees.iter().take(MAX_TREES).map(|tree| tree.to_string()).collect(),
+        text: sol.trees.first().map(|tree| format_tree(tree)).unwrap_or_default(),
+    }
+}
+
+fn tree_from_js(tree: &str) -> Result<Node, JsValue> {
+    tree.trim().parse().map_err(|e| JsValue::from_str(&format!("Invalid tree: {e}")))
+}
+
+/// `RepeatCost` named like in `SolverConfig` (`free`, `soft_no`, `hard_no`), free when missing
+fn repeat_cost_from_js(value: JsValue) -> Result<RepeatCost, JsValue> {
+    if value.is_undefined() || value.is_null() {
+        return Ok(RepeatCost::Free);
+    }
+    from_value(value).map_err(|e| JsValue::from_str(&format!("Invalid repeat cost: {e}")))
+}
+
+fn optional_words_from_js(words: JsValue) -> Result<Option<Vec<String>>, JsValue> {
+    if words.is_undefined() || words.is_null() {
+        return Ok(None);
     }
+    from_value(words).map(Some).map_err(|e| JsValue::from_str(&format!("Words must be an array of strings: {e}")))
 }
 
 /// WebAssembly entry point: solve for the provided words and return all optimal trees.
+/// `max_depth` caps the number of questions on any path; an infeasible cap is reported
+/// as an error naming the minimum feasible depth. `max_consecutive_nos` caps runs of No answers.
 #[wasm_bindgen]
 pub fn solve_words(
     words: JsValue,
     allow_repeat: bool,
     prioritize_soft_no: bool,
     redeeming_yes: u32,
+    max_depth: Option<u32>,
+    max_consecutive_nos: Option<u32>,
 ) -> Result<JsValue, JsValue> {
     let words_vec = words_from_js(words)?;
-    if words_vec.is_empty() {
-        return Err(JsValue::from_str("Please supply at least one word."));
+    let config = SolverConfig {
+        max_depth,
+        max_consecutive_nos,
+        ..SolverConfig::new(allow_repeat, prioritize_soft_no, redeeming_yes)
+    };
+    result_to_js(try_minimal_trees(&words_vec, &config), config.repeat_cost)
+}
+
+/// Stepwise solver that keeps the page responsive: call `step` from an idle loop or a Web
+/// Worker until it reports `done`, showing its progress in between, then read `result`.
+/// `cancel` (or `free`) abandons the search.
+#[wasm_bindgen]
+pub struct Solver {
+    inner: StepSolver,
+}
+
+#[wasm_bindgen]
+impl Solver {
+    /// `config` takes `SolverConfig` fields (`allow_repeat`, `max_repeats`, `repeat_cost`,
+    /// `prioritize_soft_no`, `redeeming_yes`, `weights`, `max_depth`, `max_consecutive_nos`,
+    /// `naturalness`); missing ones keep their defaults. `naturalness` is off by default, so
+    /// trees come in search order; pass `NaturalnessWeights` fields (`{}` for the defaults) to
+    /// get the most natural tree first, as the web page does.
+    #[wasm_bindgen(constructor)]
+    pub fn new(words: JsValue, config: JsValue) -> Result<Solver, JsValue> {
+        let words = words_from_js(words)?;
+        let config = if config.is_undefined() || config.is_null() {
+            SolverConfig::default()
+        } else {
+            from_value(config).map_err(|e| JsValue::from_str(&format!("Invalid solver config: {e}")))?
+        };
+        Ok(Solver { inner: StepSolver::new(words, config) })
     }
-    if words_vec.len() > 32 {
-        return Err(JsValue::from_str("Solver supports up to 32 words."));
+
+    /// Expand up to `budget` subproblems, returning the progress
+    /// (`expanded`, `memo_entries`, `done`, `cancelled`).
+    pub fn step(&mut self, budget: u32) -> JsValue {
+        to_value(&self.inner.step(budget.into())).expect("serialize progress")
     }
 
-    let sol = minimal_trees(&words_vec, allow_repeat, prioritize_soft_no, redeeming_yes);
-    to_value(&summary_from_solution(&sol))
-        .map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+    pub fn progress(&self) -> JsValue {
+        to_value(&self.inner.progress()).expect("serialize progress")
+    }
+
+    pub fn cancel(&mut self) {
+        self.inner.cancel();
+    }
+
+    /// The same solution object as `solve_words`, once `step` reports `done`.
+    pub fn result(&self) -> Result<JsValue, JsValue> {
+        match self.inner.result() {
+            Some(result) => result_to_js(result.clone(), self.inner.config().repeat_cost),
+            None => Err(JsValue::from_str("The solver has not finished.")),
+        }
+    }
+
+    /// The solution object of `result`, restricted to the optimal trees matching `query`
+    /// (see `Query` for the syntax); an error if the query is invalid or nothing matches.
+    pub fn query(&self, query: &str) -> Result<JsValue, JsValue> {
+        let sol = match self.inner.result() {
+            Some(Ok(sol)) => sol,
+            _ => return Err(JsValue::from_str("The solver has no solution to query.")),
+        };
+        let query: Query = query.parse().map_err(|e| JsValue::from_str(&format!("Invalid query: {e}")))?;
+        let repeat_cost = self.inner.config().repeat_cost;
+        let matching = sol.filter(&query, repeat_cost);
+        if matching.trees.is_empty() {
+            return Err(JsValue::from_str("No optimal tree matches the query."));
+        }
+        result_to_js(Ok(matching), repeat_cost)
+    }
+
+    /// Resolve the user's picks in the merged tree to one concrete tree. `picks` is an array of
+    /// `[path, option]` pairs, most important first; see `MergedNode::resolve`. Returns the tree
+    /// (`tree` in notation and `text`), every choice node on it with its `selected` and `valid`
+    /// options, and the paths of `ignored` picks.
+    pub fn resolve(&self, picks: JsValue) -> Result<JsValue, JsValue> {
+        let sol = match self.inner.result() {
+            Some(Ok(sol)) => sol,
+            _ => return Err(JsValue::from_str("The solver has no solution to resolve.")),
+        };
+        let picks: Vec<(String, usize)> =
+            from_value(picks).map_err(|e| JsValue::from_str(&format!("Picks must be [path, option] pairs: {e}")))?;
+        let picks = picks
+            .iter()
+            .map(|(path, idx)| Ok((path_from_js(path)?, *idx)))
+            .collect::<Result<Vec<_>, JsValue>>()?;
+        let resolution = MergedNode::merge(&sol.trees)
+            .resolve(&sol.trees, &picks)
+            .ok_or_else(|| JsValue::from_str("No tree satisfies these constraints."))?;
+        let out = WasmResolution {
+            tree: resolution.tree.to_string(),
+            text: format_tree(&resolution.tree),
+            choices: resolution
+                .choices
+                .iter()
+                .map(|choice| WasmChoice {
+                    path: path_to_js(&choice.path),
+                    selected: choice.selected,
+                    valid: choice.valid.clone(),
+                })
+                .collect(),
+            ignored: resolution.ignored.iter().map(|path| path_to_js(path)).collect(),
+        };
+        to_value(&out).map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+    }
+}
+
+/// Built-in word sets (`name`, `description`, `words`) for the web UI's dropdown.
+#[wasm_bindgen]
+pub fn word_sets() -> JsValue {
+    to_value(wordsets::ALL).expect("serialize word sets")
+}
+
+/// Words of the built-in set called `name` (see `wordsets::by_name`).
+#[wasm_bindgen]
+pub fn word_set(name: &str) -> Result<JsValue, JsValue> {
+    let set = wordsets::by_name(name).ok_or_else(|| JsValue::from_str(&format!("Unknown word set: {name}")))?;
+    to_value(set.words).map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Render a tree written in compact notation as `ascii`, `notation`, `dot`, `mermaid`,
+/// `script`, `svg` or `paths`, like the command-line `render`. `repeat_cost` (optional)
+/// is what a Repeat's No edge counts as in `paths`.
+#[wasm_bindgen]
+pub fn render_tree(tree: &str, format: &str, repeat_cost: JsValue) -> Result<String, JsValue> {
+    let tree = tree_from_js(tree)?;
+    let repeat_cost = repeat_cost_from_js(repeat_cost)?;
+    Ok(match format {
+        "ascii" => format_tree(&tree),
+        "notation" => tree.to_string(),
+        "dot" => tree.to_dot(),
+        "mermaid" => tree.to_mermaid(),
+        "script" => format_script(&tree, &ScriptTemplates::default()),
+        "svg" => render_svg(&tree, &SvgOptions::default()),
+        "paths" => format_word_paths(&word_paths(&tree, repeat_cost)),
+        _ => return Err(JsValue::from_str(&format!("Unknown format: {format}"))),
+    })
+}
+
+/// Per-word path reports (questions, expected answers, No counts) of a tree in compact notation,
+/// counting a Repeat's No edge as the optional `repeat_cost` says.
+#[wasm_bindgen]
+pub fn tree_paths(tree: &str, repeat_cost: JsValue) -> Result<JsValue, JsValue> {
+    let paths = word_paths(&tree_from_js(tree)?, repeat_cost_from_js(repeat_cost)?);
+    to_value(&paths).map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// `validate_tree` problems of a tree in compact notation, as messages. With `words`, also
+/// checks that the tree reaches exactly those words.
+#[wasm_bindgen]
+pub fn tree_issues(tree: &str, words: JsValue) -> Result<JsValue, JsValue> {
+    let words = optional_words_from_js(words)?;
+    let issues: Vec<String> =
+        validate_tree(&tree_from_js(tree)?, words.as_deref()).iter().map(ToString::to_string).collect();
+    to_value(&issues).map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Cost (as in `solve_words`), validation issues, word paths and `format_tree` text of a tree
+/// edited by the user, in compact notation. `repeat_cost` (optional) is what a Repeat's No
+/// edge counts as, like `SolverConfig::repeat_cost`.
+#[wasm_bindgen]
+pub fn evaluate_tree(tree: &str, words: JsValue, redeeming_yes: u32, repeat_cost: JsValue) -> Result<JsValue, JsValue> {
+    let words = optional_words_from_js(words)?;
+    let tree = tree_from_js(tree)?;
+    let repeat_cost = repeat_cost_from_js(repeat_cost)?;
+    let evaluation = WasmTreeEvaluation {
+        cost: cost_summary(&tree_cost_with_repeats(&tree, redeeming_yes, repeat_cost)),
+        issues: validate_tree(&tree, words.as_deref()).iter().map(ToString::to_string).collect(),
+        word_paths: word_paths(&tree, repeat_cost),
+        text: format_tree(&tree),
+    };
+    to_value(&evaluation).map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
 }
 
-/// Convenience helper exposed to JS: return the Zodiac word list.
+/// `diff` of two trees in compact notation: changed questions, moved subtrees and words whose
+/// path changed, plus the `format_diff` text annotating the second tree. `repeat_cost`
+/// (optional) is what a Repeat's No edge counts as in the word statistics.
 #[wasm_bindgen]
-pub fn zodiac_words() -> JsValue {
-    let words = vec![
-        "aries",
-        "taurus",
-        "gemini",
-        "cancer",
-        "leo",
-        "virgo",
-        "libra",
-        "scorpio",
-        "sagittarius",
-        "capricorn",
-        "aquarius",
-        "pisces",
-    ];
-    to_value(&words).expect("serialize zodiac words")
+pub fn diff_trees(before: &str, after: &str, repeat_cost: JsValue) -> Result<JsValue, JsValue> {
+    let (before, after) = (tree_from_js(before)?, tree_from_js(after)?);
+    let changes = diff(&before, &after, repeat_cost_from_js(repeat_cost)?);
+    let text = format_diff(&changes, &after);
+    to_value(&WasmTreeDiff { diff: changes, text }).map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
 }
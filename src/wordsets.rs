@@ -0,0 +1,131 @@
+use serde::Serialize;
+
+/// A named list of words commonly used in mentalism routines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct WordSet {
+    /// Lookup name, lowercase with dashes
+    pub name: &'static str,
+    pub description: &'static str,
+    pub words: &'static [&'static str],
+}
+
+impl WordSet {
+    /// The words as owned strings, ready for the solver
+    pub fn to_words(&self) -> Vec<String> {
+        self.words.iter().map(|word| word.to_string()).collect()
+    }
+}
+
+pub const ZODIAC: WordSet = WordSet {
+    name: "zodiac",
+    description: "The twelve Western star signs",
+    words: &[
+        "aries",
+        "taurus",
+        "gemini",
+        "cancer",
+        "leo",
+        "virgo",
+        "libra",
+        "scorpio",
+        "sagittarius",
+        "capricorn",
+        "aquarius",
+        "pisces",
+    ],
+};
+
+pub const CHINESE_ZODIAC: WordSet = WordSet {
+    name: "chinese-zodiac",
+    description: "The twelve animals of the Chinese zodiac",
+    words: &["rat", "ox", "tiger", "rabbit", "dragon", "snake", "horse", "goat", "monkey", "rooster", "dog", "pig"],
+};
+
+pub const PLANETS: WordSet = WordSet {
+    name: "planets",
+    description: "The eight planets of the solar system",
+    words: &["mercury", "venus", "earth", "mars", "jupiter", "saturn", "uranus", "neptune"],
+};
+
+pub const MONTHS: WordSet = WordSet {
+    name: "months",
+    description: "The months of the year",
+    words: &[
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ],
+};
+
+pub const WEEKDAYS: WordSet = WordSet {
+    name: "weekdays",
+    description: "The days of the week",
+    words: &["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"],
+};
+
+/// Card names without their leading "The"; multi-word names keep their spaces,
+/// which positional questions count like any other character
+pub const TAROT: WordSet = WordSet {
+    name: "tarot",
+    description: "The 22 major arcana of the tarot",
+    words: &[
+        "fool",
+        "magician",
+        "high priestess",
+        "empress",
+        "emperor",
+        "hierophant",
+        "lovers",
+        "chariot",
+        "strength",
+        "hermit",
+        "wheel of fortune",
+        "justice",
+        "hanged man",
+        "death",
+        "temperance",
+        "devil",
+        "tower",
+        "star",
+        "moon",
+        "sun",
+        "judgement",
+        "world",
+    ],
+};
+
+pub const SUITS: WordSet = WordSet {
+    name: "suits",
+    description: "The four suits of a deck of playing cards",
+    words: &["hearts", "diamonds", "clubs", "spades"],
+};
+
+pub const CARD_VALUES: WordSet = WordSet {
+    name: "card-values",
+    description: "The thirteen values of a deck of playing cards",
+    words: &["ace", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "jack", "queen", "king"],
+};
+
+pub const COLORS: WordSet = WordSet {
+    name: "colors",
+    description: "Ten common color names",
+    words: &["red", "orange", "yellow", "green", "blue", "purple", "pink", "brown", "black", "white"],
+};
+
+/// Every built-in word set, in display order
+pub const ALL: &[WordSet] = &[ZODIAC, CHINESE_ZODIAC, PLANETS, MONTHS, WEEKDAYS, TAROT, SUITS, CARD_VALUES, COLORS];
+
+/// Look up a built-in word set by name, ignoring case and treating `_` and spaces as `-`
+pub fn by_name(name: &str) -> Option<&'static WordSet> {
+    let name = name.trim().to_ascii_lowercase().replace(['_', ' '], "-");
+    ALL.iter().find(|set| set.name == name)
+}